@@ -0,0 +1,1168 @@
+//! Compute-unit benchmarks for the AMM instructions
+//!
+//! Runs every instruction against the built program in LiteSVM and compares the
+//! consumed compute units with the checked-in `compute_units.baseline`. An
+//! instruction missing from the baseline fails the run like a regression does.
+//!
+//! ```sh
+//! anchor build && cargo bench -p amm --bench compute_units
+//! ```
+//!
+//! Set `UPDATE_CU_BASELINE=1` to rewrite the baseline after an intended change and
+//! `CU_REGRESSION_THRESHOLD` to override the allowed regression in percent.
+//!
+//! The harness landed after `Config` moved to its zero-copy layout, so the baseline
+//! only covers that layout. There are no numbers for the Borsh `Config` it
+//! replaced, and the baseline stays empty until an `UPDATE_CU_BASELINE=1` run
+//! against a built program records it.
+#![allow(deprecated)]
+
+use std::{collections::BTreeMap, env, fs, path::PathBuf, process};
+
+use amm::{
+    accounts, instruction, SwapLeg, CONFIG_SEED, EMERGENCY_WITHDRAW_DELAY, FACTORY_SEED,
+    FARM_LP_VAULT_SEED, FARM_REWARD_VAULT_SEED, FARM_SEED, FEE_BASIS_POINTS,
+    FLASH_LOAN_FEE_BASIS_POINTS, LP_LOCK_SEED, LP_LOCK_VAULT_SEED, LP_MINT_SEED, PAUSE_WITHDRAW,
+    POOL_INDEX_SEED, STAKE_POSITION_SEED, VAULT_X_SEED, VAULT_Y_SEED, WSOL_TEMP_SEED,
+};
+use anchor_lang::{
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        pubkey::Pubkey,
+        system_instruction,
+    },
+    system_program, Discriminator, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address, spl_associated_token_account},
+    token::spl_token::{self, native_mint},
+};
+use litesvm::LiteSVM;
+use solana_sdk::{
+    account::Account, clock::Clock, signature::Keypair, signer::Signer, transaction::Transaction,
+};
+
+/// Allowed increase over the baseline before a benchmark fails, in percent
+const DEFAULT_THRESHOLD: f64 = 5.0;
+
+const FEE: u16 = 30;
+const DECIMALS: u8 = 6;
+const LIQUIDITY: u64 = 1_000_000_000;
+const SWAP_AMOUNT: u64 = 10_000_000;
+const REWARD_RATE: u64 = 1_000;
+
+/// Seed of the pool-less config written in the legacy layout for `migrate_config`
+const LEGACY_SEED: u64 = 42;
+
+/// PDAs and user token accounts of a pool
+struct Pool {
+    seed: u64,
+    config: Pubkey,
+    pool_index: Pubkey,
+    mint_x: Pubkey,
+    mint_y: Pubkey,
+    lp_mint: Pubkey,
+    vault_x: Pubkey,
+    vault_y: Pubkey,
+    user_x: Pubkey,
+    user_y: Pubkey,
+    user_lp: Pubkey,
+}
+
+/// PDAs of the LP farm and the user's stake
+struct FarmAccounts {
+    reward_mint: Pubkey,
+    farm: Pubkey,
+    reward_vault: Pubkey,
+    lp_vault: Pubkey,
+    position: Pubkey,
+    user_reward: Pubkey,
+}
+
+/// The program loaded into LiteSVM with a funded user acting as admin, authority and trader
+struct Bench {
+    svm: LiteSVM,
+    user: Keypair,
+    factory: Pubkey,
+    pool_count: u64,
+    results: Vec<(&'static str, u64)>,
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &amm::ID).0
+}
+
+impl Bench {
+    /// Load the program and fund the user
+    fn new() -> Self {
+        let mut svm = LiteSVM::new();
+        let program = program_path();
+        if let Err(err) = svm.add_program_from_file(amm::ID, &program) {
+            eprintln!(
+                "cannot load {}: {err}, run `anchor build` first",
+                program.display()
+            );
+            process::exit(1);
+        }
+
+        let user = Keypair::new();
+        svm.airdrop(&user.pubkey(), 100_000_000_000)
+            .expect("airdrop");
+
+        Self {
+            svm,
+            user,
+            factory: pda(&[FACTORY_SEED.as_bytes()]),
+            pool_count: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// Sign and send instructions with the user as fee payer, returning the consumed units
+    fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Result<u64, String> {
+        let mut all_signers = vec![&self.user];
+        all_signers.extend_from_slice(signers);
+
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.user.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        // identical instructions sent twice must not collide on their signature
+        self.svm.expire_blockhash();
+
+        result
+            .map(|meta| meta.compute_units_consumed)
+            .map_err(|failed| format!("{:?}\n{}", failed.err, failed.meta.logs.join("\n")))
+    }
+
+    /// Build an AMM instruction
+    fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+        Instruction {
+            program_id: amm::ID,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    /// Run an AMM instruction and record its compute units under `name`
+    fn measure(&mut self, name: &'static str, ix: Instruction) {
+        let units = self
+            .send(&[ix], &[])
+            .unwrap_or_else(|err| panic!("{name} failed: {err}"));
+        self.results.push((name, units));
+    }
+
+    /// Run a setup instruction without recording it
+    fn exec(&mut self, what: &str, ix: Instruction) {
+        self.send(&[ix], &[])
+            .unwrap_or_else(|err| panic!("{what} failed: {err}"));
+    }
+
+    /// Move the clock forward by `seconds` and `slots`
+    fn advance(&mut self, seconds: i64, slots: u64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        clock.slot += slots;
+        self.svm.set_sysvar(&clock);
+    }
+
+    fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    fn token_amount(&self, account: &Pubkey) -> u64 {
+        let account = self.svm.get_account(account).expect("token account");
+        spl_token::state::Account::unpack(&account.data)
+            .expect("unpack token account")
+            .amount
+    }
+
+    fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let space = spl_token::state::Mint::LEN;
+        let ixs = [
+            system_instruction::create_account(
+                &self.user.pubkey(),
+                &mint.pubkey(),
+                self.svm.minimum_balance_for_rent_exemption(space),
+                space as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &self.user.pubkey(),
+                None,
+                DECIMALS,
+            )
+            .expect("initialize mint"),
+        ];
+        self.send(&ixs, &[&mint]).expect("create mint");
+
+        mint.pubkey()
+    }
+
+    fn create_funded_ata(&mut self, mint: Pubkey, amount: u64) -> Pubkey {
+        let owner = self.user.pubkey();
+        let ata = get_associated_token_address(&owner, &mint);
+        let ixs = [
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &owner,
+                &owner,
+                &mint,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(&spl_token::ID, &mint, &ata, &owner, &[], amount)
+                .expect("mint to"),
+        ];
+        self.send(&ixs, &[]).expect("fund token account");
+
+        ata
+    }
+
+    /// Token transfer signed by the user, used as flash loan and flash swap callback
+    fn repayment(&self, from: Pubkey, to: Pubkey, amount: u64) -> Instruction {
+        spl_token::instruction::transfer(
+            &spl_token::ID,
+            &from,
+            &to,
+            &self.user.pubkey(),
+            &[],
+            amount,
+        )
+        .expect("transfer")
+    }
+
+    fn pool(&self, seed: u64, mint_x: Pubkey, mint_y: Pubkey) -> Pool {
+        let seed_bytes = seed.to_le_bytes();
+        let lp_mint = pda(&[LP_MINT_SEED.as_bytes(), &seed_bytes]);
+        let owner = self.user.pubkey();
+
+        Pool {
+            seed,
+            config: pda(&[CONFIG_SEED.as_bytes(), &seed_bytes]),
+            pool_index: pda(&[POOL_INDEX_SEED.as_bytes(), &self.pool_count.to_le_bytes()]),
+            mint_x,
+            mint_y,
+            lp_mint,
+            vault_x: pda(&[VAULT_X_SEED.as_bytes(), &seed_bytes]),
+            vault_y: pda(&[VAULT_Y_SEED.as_bytes(), &seed_bytes]),
+            user_x: get_associated_token_address(&owner, &mint_x),
+            user_y: get_associated_token_address(&owner, &mint_y),
+            user_lp: get_associated_token_address(&owner, &lp_mint),
+        }
+    }
+
+    fn wsol_temp(&self) -> Pubkey {
+        pda(&[WSOL_TEMP_SEED.as_bytes(), self.user.pubkey().as_ref()])
+    }
+
+    /// Make sure the native mint account exists for the SOL pool
+    fn set_native_mint(&mut self) {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        let mint = spl_token::state::Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        };
+        spl_token::state::Mint::pack(mint, &mut data).expect("pack native mint");
+
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm
+            .set_account(native_mint::ID, account)
+            .expect("set native mint");
+    }
+
+    /// Program data of the upgradeable program, `add_program` loads it without one
+    fn program_data(&self) -> Pubkey {
+        Pubkey::find_program_address(&[amm::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+    }
+
+    /// Record the user as the program's upgrade authority so it can create the factory
+    fn set_program_data(&mut self) {
+        let state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(self.user.pubkey()),
+        };
+        let len = UpgradeableLoaderState::size_of_programdata_metadata();
+        let account = Account::new_data_with_space(
+            self.svm.minimum_balance_for_rent_exemption(len),
+            &state,
+            len,
+            &bpf_loader_upgradeable::ID,
+        )
+        .expect("serialize program data");
+        self.svm
+            .set_account(self.program_data(), account)
+            .expect("set program data");
+    }
+
+    fn initialize_factory(&mut self) -> Instruction {
+        let accounts = accounts::InitializeFactory {
+            admin: self.user.pubkey(),
+            program_data: self.program_data(),
+            factory: self.factory,
+            system_program: system_program::ID,
+        };
+        let data = instruction::InitializeFactory {
+            protocol_fee: 0,
+            fee_tiers: vec![FEE],
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn update_factory(&mut self) -> Instruction {
+        let accounts = accounts::UpdateFactory {
+            admin: self.user.pubkey(),
+            factory: self.factory,
+        };
+        let data = instruction::UpdateFactory {
+            new_admin: self.user.pubkey(),
+            protocol_fee: 0,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn manage_fee_tiers(&self) -> accounts::ManageFeeTiers {
+        accounts::ManageFeeTiers {
+            admin: self.user.pubkey(),
+            factory: self.factory,
+        }
+    }
+
+    fn initialize(&mut self, pool: &Pool) -> Instruction {
+        let accounts = accounts::Initialize {
+            authority: self.user.pubkey(),
+            factory: self.factory,
+            config: pool.config,
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            lp_mint: pool.lp_mint,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            canonical_pool: None,
+            pool_index: pool.pool_index,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let data = instruction::Initialize {
+            seed: pool.seed,
+            fee: FEE,
+        };
+        self.pool_count += 1;
+
+        Self::ix(accounts, data)
+    }
+
+    fn deposit_accounts(&self, pool: &Pool) -> accounts::Deposit {
+        accounts::Deposit {
+            user: self.user.pubkey(),
+            config: pool.config,
+            lp_mint: pool.lp_mint,
+            user_x: Some(pool.user_x),
+            user_y: Some(pool.user_y),
+            wsol_temp: None,
+            native_mint: None,
+            user_lp: pool.user_lp,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    fn deposit(&self, pool: &Pool, amount: u64) -> Instruction {
+        let data = instruction::Deposit {
+            max_x: amount,
+            max_y: amount,
+            min_lp: 1,
+            min_x: 0,
+            min_y: 0,
+        };
+
+        Self::ix(self.deposit_accounts(pool), data)
+    }
+
+    /// Deposit paying the native X side with SOL through the temporary wSOL account
+    fn deposit_native(&self, pool: &Pool, amount: u64) -> Instruction {
+        let mut accounts = self.deposit_accounts(pool);
+        accounts.user_x = None;
+        accounts.wsol_temp = Some(self.wsol_temp());
+        accounts.native_mint = Some(native_mint::ID);
+        let data = instruction::Deposit {
+            max_x: amount,
+            max_y: amount,
+            min_lp: 1,
+            min_x: 0,
+            min_y: 0,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn set_swap_limits(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::SetSwapLimits {
+            authority: self.user.pubkey(),
+            config: pool.config,
+        };
+        let data = instruction::SetSwapLimits {
+            max_price_impact: 0,
+            max_trade_size: 0,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn set_dynamic_fee(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::SetDynamicFee {
+            authority: self.user.pubkey(),
+            config: pool.config,
+            factory: self.factory,
+        };
+        // left disabled so the swaps below pay the static fee
+        let data = instruction::SetDynamicFee {
+            enabled: false,
+            min_fee: FEE,
+            max_fee: FEE,
+            volatility_factor: 0,
+            decay_period: 3_600,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn manage_circuit_breaker(&self, pool: &Pool) -> accounts::ManageCircuitBreaker {
+        accounts::ManageCircuitBreaker {
+            authority: self.user.pubkey(),
+            config: pool.config,
+        }
+    }
+
+    fn set_pause(&self, pool: &Pool, paused: u8) -> Instruction {
+        let accounts = accounts::SetPause {
+            authority: self.user.pubkey(),
+            config: pool.config,
+        };
+
+        Self::ix(accounts, instruction::SetPause { paused })
+    }
+
+    fn swap(&self, pool: &Pool, is_x_to_y: bool) -> Instruction {
+        let accounts = accounts::Swap {
+            user: self.user.pubkey(),
+            config: pool.config,
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            user_x: pool.user_x,
+            user_y: pool.user_y,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+            recipient: None,
+        };
+        let data = instruction::Swap {
+            is_x_to_y,
+            amount_in: SWAP_AMOUNT,
+            min_out: 1,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn swap_v2(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::SwapV2 {
+            user: self.user.pubkey(),
+            config: pool.config,
+            user_in: pool.user_x,
+            user_out: pool.user_y,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+        };
+        let data = instruction::SwapV2 {
+            amount_in: SWAP_AMOUNT,
+            min_out: 1,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn swap_v3(&self, pool: &Pool, is_x_to_y: bool) -> Instruction {
+        let (mint_in, mint_out, user_in, user_out) = if is_x_to_y {
+            (pool.mint_x, pool.mint_y, pool.user_x, pool.user_y)
+        } else {
+            (pool.mint_y, pool.mint_x, pool.user_y, pool.user_x)
+        };
+        // SOL is paid or received through the temporary wSOL account
+        let native_in = mint_in == native_mint::ID;
+        let native_out = mint_out == native_mint::ID;
+        let accounts = accounts::SwapV3 {
+            user: self.user.pubkey(),
+            config: pool.config,
+            mint_in,
+            mint_out,
+            user_in: (!native_in).then_some(user_in),
+            user_out: (!native_out).then_some(user_out),
+            recipient: None,
+            wsol_temp: (native_in || native_out).then(|| self.wsol_temp()),
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let data = instruction::SwapV3 {
+            amount_in: SWAP_AMOUNT,
+            min_out: 1,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    /// Two hops, X to Y on the first pool and Y to Z on the second
+    fn route_swap(&self, first: &Pool, second: &Pool) -> Instruction {
+        let accounts = accounts::RouteSwap {
+            user: self.user.pubkey(),
+            user_in: first.user_x,
+            user_out: second.user_y,
+            token_program: spl_token::ID,
+        };
+        let data = instruction::RouteSwap {
+            amount_in: SWAP_AMOUNT,
+            min_out: 1,
+            hops: 2,
+        };
+
+        let mut ix = Self::ix(accounts, data);
+        for pool in [first, second] {
+            ix.accounts.extend([
+                AccountMeta::new(pool.config, false),
+                AccountMeta::new(pool.vault_x, false),
+                AccountMeta::new(pool.vault_y, false),
+            ]);
+        }
+        ix
+    }
+
+    /// One X to Y leg on each pool
+    fn batch_swap(&self, first: &Pool, second: &Pool) -> Instruction {
+        let accounts = accounts::BatchSwap {
+            user: self.user.pubkey(),
+            token_program: spl_token::ID,
+        };
+        let leg = SwapLeg {
+            is_x_to_y: true,
+            amount_in: SWAP_AMOUNT,
+            min_out: 1,
+        };
+        let data = instruction::BatchSwap {
+            legs: vec![leg, leg],
+            all_or_nothing: true,
+        };
+
+        let mut ix = Self::ix(accounts, data);
+        for pool in [first, second] {
+            ix.accounts.extend([
+                AccountMeta::new(pool.config, false),
+                AccountMeta::new(pool.vault_x, false),
+                AccountMeta::new(pool.vault_y, false),
+                AccountMeta::new(pool.user_x, false),
+                AccountMeta::new(pool.user_y, false),
+            ]);
+        }
+        ix
+    }
+
+    /// Borrow X and repay it plus the fee through a token transfer callback
+    fn flash_loan(&self, pool: &Pool) -> Instruction {
+        let fee =
+            (SWAP_AMOUNT * FLASH_LOAN_FEE_BASIS_POINTS as u64).div_ceil(FEE_BASIS_POINTS as u64);
+        let repay = self.repayment(pool.user_x, pool.vault_x, SWAP_AMOUNT + fee);
+
+        let accounts = accounts::FlashLoan {
+            user: self.user.pubkey(),
+            config: pool.config,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            receiver_x: pool.user_x,
+            receiver_y: pool.user_y,
+            receiver_program: spl_token::ID,
+            token_program: spl_token::ID,
+        };
+        let data = instruction::FlashLoan {
+            amount_x: SWAP_AMOUNT,
+            amount_y: 0,
+            data: repay.data,
+        };
+
+        let mut ix = Self::ix(accounts, data);
+        ix.accounts.extend(repay.accounts);
+        ix
+    }
+
+    /// Take Y out and pay for it in X through a token transfer callback
+    fn flash_swap(&self, pool: &Pool) -> Instruction {
+        let amount_y_out = amm::Config::calculate_amount_out_with_fee(
+            FEE,
+            SWAP_AMOUNT,
+            self.token_amount(&pool.vault_x),
+            self.token_amount(&pool.vault_y),
+        )
+        .expect("quote");
+        let pay = self.repayment(pool.user_x, pool.vault_x, SWAP_AMOUNT);
+
+        let accounts = accounts::FlashSwap {
+            user: self.user.pubkey(),
+            config: pool.config,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            receiver_x: pool.user_x,
+            receiver_y: pool.user_y,
+            callback_program: spl_token::ID,
+            token_program: spl_token::ID,
+        };
+        let data = instruction::FlashSwap {
+            amount_x_out: 0,
+            amount_y_out,
+            data: pay.data,
+        };
+
+        let mut ix = Self::ix(accounts, data);
+        ix.accounts.extend(pay.accounts);
+        ix
+    }
+
+    fn withdraw_accounts(&self, pool: &Pool) -> accounts::Withdraw {
+        accounts::Withdraw {
+            user: self.user.pubkey(),
+            config: pool.config,
+            lp_mint: pool.lp_mint,
+            user_x: Some(pool.user_x),
+            user_y: Some(pool.user_y),
+            user_lp: pool.user_lp,
+            recipient_x: None,
+            recipient_y: None,
+            wsol_temp: None,
+            native_mint: None,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    fn withdraw(&self, pool: &Pool) -> Instruction {
+        let data = instruction::Withdraw {
+            lp_amount: LIQUIDITY / 10,
+            min_x: 0,
+            min_y: 0,
+        };
+
+        Self::ix(self.withdraw_accounts(pool), data)
+    }
+
+    /// Withdraw receiving the native X side as SOL through the temporary wSOL account
+    fn withdraw_native(&self, pool: &Pool) -> Instruction {
+        let mut accounts = self.withdraw_accounts(pool);
+        accounts.user_x = None;
+        accounts.wsol_temp = Some(self.wsol_temp());
+        accounts.native_mint = Some(native_mint::ID);
+        let data = instruction::Withdraw {
+            lp_amount: LIQUIDITY / 10,
+            min_x: 0,
+            min_y: 0,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn withdraw_single(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::WithdrawSingle {
+            user: self.user.pubkey(),
+            config: pool.config,
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            lp_mint: pool.lp_mint,
+            user_out: pool.user_x,
+            user_lp: pool.user_lp,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+        };
+        let data = instruction::WithdrawSingle {
+            lp_amount: LIQUIDITY / 10,
+            is_x_out: true,
+            min_out: 0,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn emergency_withdraw(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::EmergencyWithdraw {
+            user: self.user.pubkey(),
+            config: pool.config,
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            lp_mint: pool.lp_mint,
+            user_x: pool.user_x,
+            user_y: pool.user_y,
+            user_lp: pool.user_lp,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        let data = instruction::EmergencyWithdraw {
+            lp_amount: LIQUIDITY / 10,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn close_pool(&self, pool: &Pool) -> Instruction {
+        let accounts = accounts::ClosePool {
+            authority: self.user.pubkey(),
+            config: pool.config,
+            lp_mint: pool.lp_mint,
+            vault_x: pool.vault_x,
+            vault_y: pool.vault_y,
+            authority_x: pool.user_x,
+            authority_y: pool.user_y,
+            canonical_pool: None,
+            pool_index: pool.pool_index,
+            token_program: spl_token::ID,
+        };
+
+        Self::ix(accounts, instruction::ClosePool {})
+    }
+
+    /// Write a pool config as the pre-versioning program stored it, see `state::legacy`
+    fn legacy_config(&mut self) -> Pubkey {
+        let seed = LEGACY_SEED.to_le_bytes();
+        let (config, config_bump) =
+            Pubkey::find_program_address(&[CONFIG_SEED.as_bytes(), &seed], &amm::ID);
+        let (_, lp_bump) =
+            Pubkey::find_program_address(&[LP_MINT_SEED.as_bytes(), &seed], &amm::ID);
+
+        // discriminator, seed, Some(authority), mint_x, mint_y, fee, locked, bumps
+        let mut data = amm::Config::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&seed);
+        data.push(1);
+        data.extend_from_slice(self.user.pubkey().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&FEE.to_le_bytes());
+        data.extend_from_slice(&[0, config_bump, lp_bump]);
+
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: amm::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm
+            .set_account(config, account)
+            .expect("legacy config");
+
+        config
+    }
+
+    fn migrate_config(&mut self) -> Instruction {
+        let accounts = accounts::MigrateConfig {
+            payer: self.user.pubkey(),
+            config: self.legacy_config(),
+            system_program: system_program::ID,
+        };
+
+        Self::ix(accounts, instruction::MigrateConfig {})
+    }
+
+    fn farm(&mut self, pool: &Pool) -> FarmAccounts {
+        let reward_mint = self.create_mint();
+        let user_reward = self.create_funded_ata(reward_mint, 10 * LIQUIDITY);
+        let farm = pda(&[
+            FARM_SEED.as_bytes(),
+            pool.config.as_ref(),
+            reward_mint.as_ref(),
+        ]);
+
+        FarmAccounts {
+            reward_mint,
+            farm,
+            reward_vault: pda(&[FARM_REWARD_VAULT_SEED.as_bytes(), farm.as_ref()]),
+            lp_vault: pda(&[FARM_LP_VAULT_SEED.as_bytes(), farm.as_ref()]),
+            position: pda(&[
+                STAKE_POSITION_SEED.as_bytes(),
+                farm.as_ref(),
+                self.user.pubkey().as_ref(),
+            ]),
+            user_reward,
+        }
+    }
+
+    fn initialize_farm(&self, pool: &Pool, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::InitializeFarm {
+            authority: self.user.pubkey(),
+            config: pool.config,
+            lp_mint: pool.lp_mint,
+            reward_mint: farm.reward_mint,
+            farm: farm.farm,
+            reward_vault: farm.reward_vault,
+            lp_vault: farm.lp_vault,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        };
+        let data = instruction::InitializeFarm {
+            reward_authority: self.user.pubkey(),
+            reward_rate: REWARD_RATE,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn fund_farm(&self, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::FundFarm {
+            reward_authority: self.user.pubkey(),
+            farm: farm.farm,
+            funder: farm.user_reward,
+            reward_vault: farm.reward_vault,
+            token_program: spl_token::ID,
+        };
+
+        Self::ix(accounts, instruction::FundFarm { amount: LIQUIDITY })
+    }
+
+    fn set_reward_rate(&self, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::SetRewardRate {
+            reward_authority: self.user.pubkey(),
+            farm: farm.farm,
+        };
+        let data = instruction::SetRewardRate {
+            reward_rate: REWARD_RATE,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn stake_lp(&self, pool: &Pool, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::StakeLp {
+            user: self.user.pubkey(),
+            farm: farm.farm,
+            position: farm.position,
+            user_lp: pool.user_lp,
+            lp_vault: farm.lp_vault,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        };
+
+        Self::ix(
+            accounts,
+            instruction::StakeLp {
+                amount: LIQUIDITY / 10,
+            },
+        )
+    }
+
+    fn claim_rewards(&self, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::ClaimRewards {
+            user: self.user.pubkey(),
+            farm: farm.farm,
+            position: farm.position,
+            user_reward: farm.user_reward,
+            reward_vault: farm.reward_vault,
+            token_program: spl_token::ID,
+        };
+
+        Self::ix(accounts, instruction::ClaimRewards {})
+    }
+
+    fn unstake_lp(&self, pool: &Pool, farm: &FarmAccounts) -> Instruction {
+        let accounts = accounts::UnstakeLp {
+            user: self.user.pubkey(),
+            farm: farm.farm,
+            position: farm.position,
+            user_lp: pool.user_lp,
+            lp_vault: farm.lp_vault,
+            token_program: spl_token::ID,
+        };
+
+        Self::ix(
+            accounts,
+            instruction::UnstakeLp {
+                amount: LIQUIDITY / 10,
+            },
+        )
+    }
+
+    fn lock_pdas(&self, pool: &Pool) -> (Pubkey, Pubkey) {
+        let lock = pda(&[
+            LP_LOCK_SEED.as_bytes(),
+            pool.config.as_ref(),
+            self.user.pubkey().as_ref(),
+        ]);
+        (lock, pda(&[LP_LOCK_VAULT_SEED.as_bytes(), lock.as_ref()]))
+    }
+
+    fn lock_lp(&self, pool: &Pool, unlock_ts: i64) -> Instruction {
+        let (lock, lock_vault) = self.lock_pdas(pool);
+        let accounts = accounts::LockLp {
+            owner: self.user.pubkey(),
+            config: pool.config,
+            lp_mint: pool.lp_mint,
+            owner_lp: pool.user_lp,
+            lock,
+            lock_vault,
+            token_program: spl_token::ID,
+            system_program: system_program::ID,
+        };
+        let data = instruction::LockLp {
+            amount: LIQUIDITY / 10,
+            unlock_ts,
+            linear: false,
+        };
+
+        Self::ix(accounts, data)
+    }
+
+    fn unlock_lp(&self, pool: &Pool) -> Instruction {
+        let (lock, lock_vault) = self.lock_pdas(pool);
+        let accounts = accounts::UnlockLp {
+            owner: self.user.pubkey(),
+            lock,
+            lock_vault,
+            owner_lp: pool.user_lp,
+            token_program: spl_token::ID,
+        };
+
+        Self::ix(accounts, instruction::UnlockLp {})
+    }
+
+    /// Run every instruction once, each step against the state left by the previous ones
+    fn run(&mut self) {
+        let mint_x = self.create_mint();
+        let mint_y = self.create_mint();
+        let mint_z = self.create_mint();
+        for mint in [mint_x, mint_y, mint_z] {
+            self.create_funded_ata(mint, 10 * LIQUIDITY);
+        }
+
+        // factory
+        self.set_program_data();
+        let ix = self.initialize_factory();
+        self.measure("initialize_factory", ix);
+        let ix = self.update_factory();
+        self.measure("update_factory", ix);
+        self.measure(
+            "add_fee_tier",
+            Self::ix(
+                self.manage_fee_tiers(),
+                instruction::AddFeeTier { fee: 100 },
+            ),
+        );
+        self.measure(
+            "remove_fee_tier",
+            Self::ix(
+                self.manage_fee_tiers(),
+                instruction::RemoveFeeTier { fee: 100 },
+            ),
+        );
+
+        // an X/Y pool and a Y/Z pool to route through
+        let pool = self.pool(1, mint_x, mint_y);
+        let ix = self.initialize(&pool);
+        self.measure("initialize", ix);
+        let next = self.pool(2, mint_y, mint_z);
+        let ix = self.initialize(&next);
+        self.exec("initialize second pool", ix);
+
+        self.measure("deposit_initial", self.deposit(&pool, LIQUIDITY));
+        self.measure("deposit", self.deposit(&pool, LIQUIDITY / 2));
+        self.exec("deposit second pool", self.deposit(&next, LIQUIDITY));
+
+        // pool settings, all left disabled so swaps measure the plain path
+        self.measure("set_swap_limits", self.set_swap_limits(&pool));
+        self.measure("set_dynamic_fee", self.set_dynamic_fee(&pool));
+        self.measure(
+            "set_circuit_breaker",
+            Self::ix(
+                self.manage_circuit_breaker(&pool),
+                instruction::SetCircuitBreaker {
+                    threshold: 0,
+                    window: 0,
+                },
+            ),
+        );
+        self.measure(
+            "reset_circuit_breaker",
+            Self::ix(
+                self.manage_circuit_breaker(&pool),
+                instruction::ResetCircuitBreaker {},
+            ),
+        );
+
+        // swaps
+        self.measure("swap_x_to_y", self.swap(&pool, true));
+        self.measure("swap_y_to_x", self.swap(&pool, false));
+        self.measure("swap_v2", self.swap_v2(&pool));
+        self.measure("swap_v3", self.swap_v3(&pool, true));
+        self.measure("route_swap", self.route_swap(&pool, &next));
+        self.measure("batch_swap", self.batch_swap(&pool, &next));
+        self.measure("flash_loan", self.flash_loan(&pool));
+        self.measure("flash_swap", self.flash_swap(&pool));
+
+        // farming, with a few seconds of emissions to claim
+        let farm = self.farm(&pool);
+        self.measure("initialize_farm", self.initialize_farm(&pool, &farm));
+        self.measure("fund_farm", self.fund_farm(&farm));
+        self.measure("set_reward_rate", self.set_reward_rate(&farm));
+        self.measure("stake_lp", self.stake_lp(&pool, &farm));
+        self.advance(10, 25);
+        self.measure("claim_rewards", self.claim_rewards(&farm));
+        self.measure("unstake_lp", self.unstake_lp(&pool, &farm));
+
+        // a cliff lock released after its unlock time
+        let unlock_ts = self.now() + 10;
+        self.measure("lock_lp", self.lock_lp(&pool, unlock_ts));
+        self.advance(20, 50);
+        self.measure("unlock_lp", self.unlock_lp(&pool));
+
+        // withdrawals
+        self.measure("withdraw", self.withdraw(&pool));
+        self.measure("withdraw_single", self.withdraw_single(&pool));
+
+        // native SOL on the X side of a SOL/Z pool
+        self.set_native_mint();
+        let sol = self.pool(4, native_mint::ID, mint_z);
+        let ix = self.initialize(&sol);
+        self.exec("initialize SOL pool", ix);
+        self.exec("seed SOL pool", self.deposit_native(&sol, LIQUIDITY));
+        self.measure(
+            "deposit_native_sol",
+            self.deposit_native(&sol, LIQUIDITY / 2),
+        );
+        self.measure("swap_native_sol_in", self.swap_v3(&sol, true));
+        self.measure("swap_native_sol_out", self.swap_v3(&sol, false));
+        self.measure("withdraw_native_sol", self.withdraw_native(&sol));
+
+        // emergency exit once withdrawals stay paused past the grace period
+        self.measure("set_pause", self.set_pause(&pool, PAUSE_WITHDRAW));
+        self.advance(0, EMERGENCY_WITHDRAW_DELAY);
+        self.measure("emergency_withdraw", self.emergency_withdraw(&pool));
+        self.exec("unpause", self.set_pause(&pool, 0));
+
+        // a legacy config and an empty pool
+        let ix = self.migrate_config();
+        self.measure("migrate_config", ix);
+        let empty = self.pool(3, mint_x, mint_y);
+        let ix = self.initialize(&empty);
+        self.exec("initialize empty pool", ix);
+        self.measure("close_pool", self.close_pool(&empty));
+    }
+}
+
+fn program_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/amm.so")
+}
+
+fn baseline_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/compute_units.baseline")
+}
+
+/// Parse `name units` lines, skipping blank lines and `#` comments
+fn read_baseline() -> BTreeMap<String, u64> {
+    let contents = fs::read_to_string(baseline_path()).unwrap_or_default();
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, units) = line.split_once(char::is_whitespace)?;
+            Some((name.to_string(), units.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn write_baseline(results: &[(&str, u64)]) {
+    let mut contents = String::from(
+        "# Compute units per instruction, regenerate with\n\
+         # UPDATE_CU_BASELINE=1 cargo bench -p amm --bench compute_units\n",
+    );
+    for (name, units) in results {
+        contents.push_str(&format!("{name} {units}\n"));
+    }
+
+    fs::write(baseline_path(), contents).expect("write baseline");
+}
+
+fn main() {
+    let mut bench = Bench::new();
+    bench.run();
+    let results = bench.results;
+
+    if env::var_os("UPDATE_CU_BASELINE").is_some() {
+        write_baseline(&results);
+        println!("wrote {}", baseline_path().display());
+        return;
+    }
+
+    let threshold = env::var("CU_REGRESSION_THRESHOLD")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_THRESHOLD);
+    let baseline = read_baseline();
+    let mut regressions = 0;
+    let mut missing = 0;
+
+    println!(
+        "{:<22} {:>10} {:>10} {:>9}",
+        "instruction", "baseline", "units", "change"
+    );
+    for &(name, units) in &results {
+        let Some(&expected) = baseline.get(name) else {
+            println!("{name:<22} {:>10} {units:>10} {:>9}", "-", "MISSING");
+            missing += 1;
+            continue;
+        };
+
+        let change = (units as f64 - expected as f64) / expected as f64 * 100.0;
+        let regressed = change > threshold;
+        println!(
+            "{name:<22} {expected:>10} {units:>10} {change:>+8.2}%{}",
+            if regressed { "  REGRESSED" } else { "" }
+        );
+        regressions += regressed as usize;
+    }
+
+    if missing > 0 {
+        eprintln!(
+            "{missing} instruction(s) have no baseline entry, record them with UPDATE_CU_BASELINE=1"
+        );
+    }
+    if regressions > 0 {
+        eprintln!(
+            "{regressions} instruction(s) regressed more than {threshold}% over the baseline"
+        );
+    }
+    if missing > 0 || regressions > 0 {
+        process::exit(1);
+    }
+}
@@ -13,6 +13,36 @@ pub const VAULT_X_SEED: &str = "vault_x";
 #[constant]
 pub const VAULT_Y_SEED: &str = "vault_y";
 
+#[constant]
+pub const CANONICAL_POOL_SEED: &str = "pool";
+
+#[constant]
+pub const FACTORY_SEED: &str = "factory";
+
+#[constant]
+pub const POOL_INDEX_SEED: &str = "pool_index";
+
+#[constant]
+pub const FARM_SEED: &str = "farm";
+
+#[constant]
+pub const FARM_REWARD_VAULT_SEED: &str = "farm_reward_vault";
+
+#[constant]
+pub const FARM_LP_VAULT_SEED: &str = "farm_lp_vault";
+
+#[constant]
+pub const STAKE_POSITION_SEED: &str = "stake";
+
+#[constant]
+pub const LP_LOCK_SEED: &str = "lp_lock";
+
+#[constant]
+pub const LP_LOCK_VAULT_SEED: &str = "lp_lock_vault";
+
+#[constant]
+pub const WSOL_TEMP_SEED: &str = "wsol_temp";
+
 // Math Constants - for calculations and validations
 #[constant]
 pub const FEE_BASIS_POINTS: i16 = 10000; // 100%
@@ -22,3 +52,65 @@ pub const MINIMUM_LIQUIDITY: u64 = 1000; // Min LP tokens to prevent division by
 
 #[constant]
 pub const MAX_FEE_BASIS_POINTS: u16 = 500; // 5%
+
+#[constant]
+pub const MAX_PROTOCOL_FEE_BASIS_POINTS: u16 = 5000; // 50% of trading fees
+
+#[constant]
+pub const FLASH_LOAN_FEE_BASIS_POINTS: u16 = 9; // 0.09%, paid to LPs
+
+#[constant]
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000; // Fixed-point scale for farm reward-per-share
+
+#[constant]
+pub const PRICE_PRECISION: u128 = 1_000_000_000_000; // Fixed-point scale for prices
+
+#[constant]
+pub const MAX_VOLATILITY_BPS: u32 = 1_000_000; // Cap on the dynamic fee volatility accumulator
+
+// Routing Limits
+#[constant]
+pub const MAX_ROUTE_HOPS: u8 = 4;
+#[constant]
+pub const MAX_BATCH_LEGS: u8 = 8;
+
+// Pause Flags - bits of `Config::paused`
+#[constant]
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+
+#[constant]
+pub const PAUSE_WITHDRAW: u8 = 1 << 1;
+
+#[constant]
+pub const PAUSE_SWAP: u8 = 1 << 2;
+
+#[constant]
+pub const PAUSE_FLASH_LOAN: u8 = 1 << 3;
+
+#[constant]
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_SWAP | PAUSE_FLASH_LOAN;
+
+#[constant]
+pub const EMERGENCY_WITHDRAW_DELAY: u64 = 216_000; // Slots withdrawals stay paused before LPs can exit (~1 day)
+
+#[constant]
+pub const EMERGENCY_WITHDRAW_RESET: u64 = 216_000; // Slots withdrawals must stay unpaused before a new pause restarts the delay (~1 day)
+
+// Config Layout
+#[constant]
+pub const CONFIG_VERSION: u8 = 1;
+pub const CONFIG_RESERVED_SPACE: usize = 56;
+
+// LP Token Metadata, limits follow the Metaplex token metadata program
+#[constant]
+pub const LP_NAME_PREFIX: &str = "AMM LP";
+
+#[constant]
+pub const LP_SYMBOL: &str = "AMMLP";
+
+pub const LP_FALLBACK_SYMBOL_LEN: usize = 4; // Leading address characters of a mint without metadata
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+// Factory Limits
+pub const MAX_FEE_TIERS: usize = 16;
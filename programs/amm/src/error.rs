@@ -49,6 +49,96 @@ pub enum AmmError {
     Unauthorized,
     #[msg("No authority set for this pool")]
     NoAuthority,
+
+    // Canonical Pool Errors
+    #[msg("Canonical pool mints must be sorted (mint_x < mint_y).")]
+    InvalidMintOrder,
+
+    // Factory Errors
+    #[msg("Too many fee tiers.")]
+    TooManyFeeTiers,
+    #[msg("Fee tier already exists.")]
+    DuplicateFeeTier,
+    #[msg("Fee is not a whitelisted fee tier.")]
+    FeeTierNotAllowed,
+    #[msg("Fee tier not found.")]
+    FeeTierNotFound,
+
+    // Flash Loan Errors
+    #[msg("A flash loan is in progress on this pool.")]
+    FlashLoanActive,
+    #[msg("Flash loan was not repaid with fee.")]
+    FlashLoanNotRepaid,
+    #[msg("Invalid flash loan receiver program.")]
+    InvalidFlashLoanReceiver,
+
+    // Routing Errors
+    #[msg("Invalid swap route.")]
+    InvalidRoute,
+    #[msg("Invalid pool vault provided.")]
+    InvalidVault,
+    #[msg("Invalid swap batch.")]
+    InvalidBatch,
+
+    // Swap Protection Errors
+    #[msg("Swap exceeds the pool's maximum price impact.")]
+    PriceImpactExceeded,
+    #[msg("Swap exceeds the pool's maximum trade size.")]
+    TradeSizeExceeded,
+    #[msg("Swap limit exceeds 100%.")]
+    InvalidSwapLimit,
+    #[msg("Invalid circuit breaker settings.")]
+    InvalidCircuitBreaker,
+
+    // Pause Errors
+    #[msg("Deposits are paused on this pool.")]
+    DepositsPaused,
+    #[msg("Withdrawals are paused on this pool.")]
+    WithdrawalsPaused,
+    #[msg("Swaps are paused on this pool.")]
+    SwapsPaused,
+    #[msg("Flash loans are paused on this pool.")]
+    FlashLoansPaused,
+    #[msg("Unknown pause flags.")]
+    InvalidPauseFlags,
+    #[msg("Emergency withdrawal is only available once withdrawals have been paused past the grace period.")]
+    EmergencyWithdrawUnavailable,
+
+    // Pool Lifecycle Errors
+    #[msg("Pool still has outstanding LP tokens.")]
+    PoolNotEmpty,
+    #[msg("Canonical pool entry does not match this pool.")]
+    InvalidCanonicalPool,
+    #[msg("Pool index entry does not match this pool.")]
+    InvalidPoolIndex,
+    #[msg("Config account is not in a layout that can be migrated.")]
+    InvalidConfigLayout,
+
+    // LP Metadata Errors
+    #[msg("LP metadata name or URI is too long.")]
+    InvalidMetadata,
+
+    // Farming Errors
+    #[msg("Unstake amount exceeds the staked balance.")]
+    InsufficientStake,
+    #[msg("No rewards to claim.")]
+    NoRewards,
+
+    // LP Lock Errors
+    #[msg("Unlock time must be in the future.")]
+    InvalidUnlockTime,
+    #[msg("No locked LP tokens have vested yet.")]
+    NothingUnlocked,
+
+    // Token Account Errors
+    #[msg("Pass exactly one of the user's output account or a recipient.")]
+    InvalidOutputAccount,
+    #[msg("No token account provided for this side of the pool.")]
+    MissingTokenAccount,
+
+    // Withdraw Errors
+    #[msg("Withdrawing the entire LP supply must use withdraw.")]
+    EntireSupplyWithdraw,
 }
 
 impl From<CurveError> for AmmError {
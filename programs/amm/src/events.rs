@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+/// Emitted when the circuit breaker pauses swaps on a pool
+#[event]
+pub struct CircuitBreakerTripped {
+    /// Seed of the pool whose swaps were paused
+    pub seed: u64,
+
+    /// Pool price (y per x, scaled by PRICE_PRECISION) after the tripping swap
+    pub price: u128,
+
+    /// Move from the window's reference price, in basis points
+    pub price_move: u32,
+
+    /// Timestamp of the tripping swap
+    pub timestamp: i64,
+}
+
+/// Emitted when a staker claims farm rewards
+#[event]
+pub struct RewardsClaimed {
+    /// Farm the rewards were paid from
+    pub farm: Pubkey,
+
+    /// Staker that claimed
+    pub user: Pubkey,
+
+    /// Reward tokens paid out
+    pub amount: u64,
+}
+
+/// Emitted when an LP exits a pool through the emergency withdrawal path
+#[event]
+pub struct EmergencyWithdrawal {
+    /// Seed of the pool
+    pub seed: u64,
+
+    /// LP that redeemed
+    pub user: Pubkey,
+
+    /// LP tokens burned
+    pub lp_amount: u64,
+
+    /// Token X sent to the LP
+    pub amount_x: u64,
+
+    /// Token Y sent to the LP
+    pub amount_y: u64,
+}
+
+/// Emitted when LP tokens are locked, so launches can point at their locked liquidity
+#[event]
+pub struct LpLocked {
+    /// Config of the pool whose LP tokens were locked
+    pub config: Pubkey,
+
+    /// Owner of the lock
+    pub owner: Pubkey,
+
+    /// LP tokens locked
+    pub amount: u64,
+
+    /// Timestamp the lock fully releases at
+    pub unlock_ts: i64,
+
+    /// Whether the lock vests linearly until `unlock_ts`
+    pub linear: bool,
+}
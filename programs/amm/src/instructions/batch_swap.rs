@@ -0,0 +1,182 @@
+use crate::{constants::*, error::AmmError, instructions::route_swap::PoolAccounts, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+/// A single swap of a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapLeg {
+    /// Swap direction (true = X to Y)
+    pub is_x_to_y: bool,
+
+    /// Amount of input token
+    pub amount_in: u64,
+
+    /// Minimum amount of output token
+    pub min_out: u64,
+}
+
+/// Accounts of a leg passed through `remaining_accounts`:
+/// `[config, vault_x, vault_y, user_in, user_out]`
+pub struct LegAccounts<'info> {
+    /// Pool the leg trades against
+    pub pool: PoolAccounts<'info>,
+
+    /// User's token account for the leg input
+    pub user_in: Account<'info, TokenAccount>,
+
+    /// User's token account for the leg output
+    pub user_out: Account<'info, TokenAccount>,
+}
+
+impl<'info> LegAccounts<'info> {
+    /// Number of remaining accounts per leg
+    pub const LEN: usize = PoolAccounts::LEN + 2;
+
+    /// Deserialize and validate a leg, returning the quoted amount out
+    pub fn load(
+        accounts: &'info [AccountInfo<'info>],
+        user: &Pubkey,
+        leg: &SwapLeg,
+    ) -> Result<(Self, u64)> {
+        let (pool_accounts, user_accounts) = accounts.split_at(PoolAccounts::LEN);
+        let pool = PoolAccounts::load(pool_accounts)?;
+
+        let user_in: Account<'info, TokenAccount> = Account::try_from(&user_accounts[0])?;
+        let user_out: Account<'info, TokenAccount> = Account::try_from(&user_accounts[1])?;
+
+        // user accounts must belong to the signer and match the leg direction
+        require_keys_eq!(user_in.owner, *user, AmmError::Unauthorized);
+        require_keys_eq!(user_out.owner, *user, AmmError::Unauthorized);
+        require!(
+            pool.direction(&user_in.mint)? == leg.is_x_to_y
+                && user_out.mint == pool.mint_out(leg.is_x_to_y)?,
+            AmmError::InvalidToken
+        );
+
+        // check positive amounts and balance
+        require!(
+            leg.amount_in > 0 && leg.min_out > 0,
+            AmmError::InvalidAmount
+        );
+        require!(
+            user_in.amount >= leg.amount_in,
+            AmmError::InsufficientBalance
+        );
+
+        // quote against the current reserves
+        let (reserve_in, reserve_out) =
+            Config::reserves_in_out(leg.is_x_to_y, pool.vault_x.amount, pool.vault_y.amount);
+        require!(reserve_in > 0 && reserve_out > 0, AmmError::ZeroBalance);
+        let config = pool.config.load()?;
+        let amount_out = config.calculate_amount_out(leg.amount_in, reserve_in, reserve_out)?;
+
+        // slippage protection and pool-level limits
+        require!(amount_out >= leg.min_out, AmmError::SlippageExceeded);
+        config.check_swap_limits(leg.amount_in, amount_out, reserve_in, reserve_out)?;
+        drop(config);
+
+        Ok((
+            Self {
+                pool,
+                user_in,
+                user_out,
+            },
+            amount_out,
+        ))
+    }
+}
+
+#[derive(Accounts)]
+pub struct BatchSwap<'info> {
+    /// User performing the swaps
+    pub user: Signer<'info>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> BatchSwap<'info> {
+    /// Validate batch swap parameters
+    pub fn validate(&self, legs: &[SwapLeg], remaining_accounts: usize) -> Result<()> {
+        // one set of leg accounts per leg
+        require!(
+            !legs.is_empty() && legs.len() <= MAX_BATCH_LEGS as usize,
+            AmmError::InvalidBatch
+        );
+        require!(
+            remaining_accounts == legs.len() * LegAccounts::LEN,
+            AmmError::InvalidBatch
+        );
+
+        Ok(())
+    }
+
+    /// Execute a validated leg: user -> vault, vault -> user, then record the swap
+    pub fn execute(
+        &self,
+        accounts: &LegAccounts<'info>,
+        leg: &SwapLeg,
+        amount_out: u64,
+    ) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: accounts.user_in.to_account_info(),
+                to: accounts.pool.vault_in(leg.is_x_to_y),
+                authority: self.user.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, leg.amount_in)?;
+
+        accounts.pool.transfer_out(
+            leg.is_x_to_y,
+            amount_out,
+            accounts.user_out.to_account_info(),
+            self.token_program.to_account_info(),
+        )?;
+
+        // track the new price for the dynamic fee, written in place so the next
+        // leg on the same pool quotes against it
+        let before = (accounts.pool.vault_x.amount, accounts.pool.vault_y.amount);
+        let after = Config::post_swap_reserves(
+            leg.is_x_to_y,
+            before.0,
+            before.1,
+            leg.amount_in,
+            amount_out,
+        )?;
+        accounts.pool.config.load_mut()?.record_swap(before, after)
+    }
+}
+
+pub fn batch_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>,
+    legs: Vec<SwapLeg>,
+    all_or_nothing: bool,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(&legs, ctx.remaining_accounts.len())?;
+
+    let user = ctx.accounts.user.key();
+
+    for (i, (leg, accounts)) in legs
+        .iter()
+        .zip(ctx.remaining_accounts.chunks(LegAccounts::LEN))
+        .enumerate()
+    {
+        // every check runs before any transfer of the leg, so a failing leg
+        // can be skipped without aborting the transaction
+        let (leg_accounts, amount_out) = match LegAccounts::load(accounts, &user, leg) {
+            Ok(loaded) => loaded,
+            Err(error) if !all_or_nothing => {
+                msg!("Skipping leg {}: {}", i, error);
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+
+        ctx.accounts.execute(&leg_accounts, leg, amount_out)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    events::RewardsClaimed,
+    state::{Farm, StakePosition},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// Staker claiming rewards
+    pub user: Signer<'info>,
+
+    /// Farm paying the rewards
+    #[account(
+        mut,
+        seeds = [FARM_SEED.as_bytes(), farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// User's stake position
+    #[account(
+        mut,
+        seeds = [STAKE_POSITION_SEED.as_bytes(), farm.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// Token account receiving the rewards
+    #[account(
+        mut,
+        token::mint = farm.reward_mint,
+    )]
+    pub user_reward: Account<'info, TokenAccount>,
+
+    /// Vault holding the funded rewards
+    #[account(
+        mut,
+        seeds = [FARM_REWARD_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClaimRewards<'info> {
+    /// Transfer rewards from the farm to the user
+    pub fn transfer_rewards(&self, amount: u64) -> Result<()> {
+        let seeds = &[
+            FARM_SEED.as_bytes(),
+            self.farm.config.as_ref(),
+            self.farm.reward_mint.as_ref(),
+            &[self.farm.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reward_vault.to_account_info(),
+                to: self.user_reward.to_account_info(),
+                authority: self.farm.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+/// Handler function for claiming settled and newly accrued farm rewards
+pub fn claim_rewards_handler(ctx: Context<ClaimRewards>) -> Result<()> {
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+    farm.update(Clock::get()?.unix_timestamp)?;
+    position.settle(farm.acc_reward_per_share)?;
+
+    let amount = position.pending;
+    require!(amount > 0, AmmError::NoRewards);
+    position.pending = 0;
+
+    ctx.accounts.transfer_rewards(amount)?;
+
+    emit!(RewardsClaimed {
+        farm: ctx.accounts.farm.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+    });
+
+    Ok(())
+}
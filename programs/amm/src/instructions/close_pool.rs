@@ -0,0 +1,165 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{CanonicalPool, Config, PoolIndex},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    /// Pool authority, receives the swept tokens and the reclaimed rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// AMM config account to be closed
+    #[account(
+        mut,
+        close = authority,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// LP token mint, SPL mints cannot be closed so it is left behind with zero supply
+    #[account(
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_x,
+        token::authority = config,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_y,
+        token::authority = config,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// Authority's token X account receiving the leftover vault balance
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_x,
+    )]
+    pub authority_x: Account<'info, TokenAccount>,
+
+    /// Authority's token Y account receiving the leftover vault balance
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_y,
+    )]
+    pub authority_y: Account<'info, TokenAccount>,
+
+    /// Canonical pool entry, required when the pool is canonical
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            CANONICAL_POOL_SEED.as_bytes(),
+            config.load()?.mint_x.as_ref(),
+            config.load()?.mint_y.as_ref(),
+            config.load()?.fee.to_le_bytes().as_ref(),
+        ],
+        bump = canonical_pool.bump,
+        constraint = canonical_pool.config == config.key() @ AmmError::InvalidCanonicalPool,
+    )]
+    pub canonical_pool: Option<Account<'info, CanonicalPool>>,
+
+    /// Registry entry of the pool, kept as a tombstone so the indexes stay dense
+    #[account(
+        mut,
+        seeds = [POOL_INDEX_SEED.as_bytes(), pool_index.index.to_le_bytes().as_ref()],
+        bump = pool_index.bump,
+        constraint = pool_index.config == config.key() @ AmmError::InvalidPoolIndex,
+    )]
+    pub pool_index: Account<'info, PoolIndex>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> ClosePool<'info> {
+    /// Validate the pool can be closed
+    pub fn validate(&self) -> Result<()> {
+        // only the pool authority can close the pool
+        self.config.load()?.can_modify(&self.authority.key())?;
+
+        // every LP must have exited, leftovers in the vaults are then unowned dust
+        require!(self.lp_mint.supply == 0, AmmError::PoolNotEmpty);
+
+        // the canonical lookup must not keep pointing at a closed pool
+        require!(
+            !self.config.load()?.is_canonical() || self.canonical_pool.is_some(),
+            AmmError::InvalidCanonicalPool
+        );
+
+        Ok(())
+    }
+
+    /// Sweep a vault's remaining balance to the authority and close it
+    pub fn sweep_and_close_vault(
+        &self,
+        vault: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+    ) -> Result<()> {
+        let (seed, bump) = {
+            let config = self.config.load()?;
+            (config.seed.to_le_bytes(), config.config_bump)
+        };
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        if vault.amount > 0 {
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: vault.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_ctx, vault.amount)?;
+        }
+
+        let close_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: self.authority.to_account_info(),
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::close_account(close_ctx)
+    }
+}
+
+/// Handler function for closing an empty pool and reclaiming its rent
+pub fn close_pool_handler(ctx: Context<ClosePool>) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+
+    let accounts = &ctx.accounts;
+    accounts.sweep_and_close_vault(&accounts.vault_x, &accounts.authority_x)?;
+    accounts.sweep_and_close_vault(&accounts.vault_y, &accounts.authority_y)?;
+
+    // the registry entry no longer points at a pool
+    ctx.accounts.pool_index.config = Pubkey::default();
+
+    // config and canonical pool are closed by their `close` constraints
+    Ok(())
+}
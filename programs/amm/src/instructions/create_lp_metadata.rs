@@ -0,0 +1,147 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    metadata::{
+        create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+        Metadata, MetadataAccount,
+    },
+    token::Mint,
+};
+
+#[derive(Accounts)]
+pub struct CreateLpMetadata<'info> {
+    /// Pays for the metadata account, must be the pool authority unless the pool has none
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// AMM config account, mint and update authority of the metadata
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// LP token mint
+    #[account(
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Metadata account of the first token mint, may be uninitialized
+    /// CHECK: address derived from the mint, deserialized in `symbol` when it exists
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), config.load()?.mint_x.as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata_x: UncheckedAccount<'info>,
+
+    /// Metadata account of the second token mint, may be uninitialized
+    /// CHECK: address derived from the mint, deserialized in `symbol` when it exists
+    #[account(
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), config.load()?.mint_y.as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata_y: UncheckedAccount<'info>,
+
+    /// Metadata account of the LP mint to be created
+    /// CHECK: initialized and validated by the token metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), lp_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key(),
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Metaplex token metadata program
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+
+    /// Rent sysvar
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> CreateLpMetadata<'info> {
+    /// Validate the metadata and build its name from the pair's symbols
+    pub fn validate(&self, uri: &str) -> Result<String> {
+        let config = self.config.load()?;
+
+        // anyone can name the LP token of a pool without authority, but only the authority sets a uri
+        if config.authority == Pubkey::default() {
+            require!(uri.is_empty(), AmmError::InvalidMetadata);
+        } else {
+            config.can_modify(&self.payer.key())?;
+        }
+
+        let symbol_x = Self::symbol(&self.metadata_x, &config.mint_x)?;
+        let symbol_y = Self::symbol(&self.metadata_y, &config.mint_y)?;
+        let name = format!("{} {}-{}", LP_NAME_PREFIX, symbol_x, symbol_y);
+        require!(
+            name.len() <= MAX_METADATA_NAME_LEN && uri.len() <= MAX_METADATA_URI_LEN,
+            AmmError::InvalidMetadata
+        );
+
+        Ok(name)
+    }
+
+    /// Symbol from a mint's metadata, or the start of its address when it has none
+    fn symbol(metadata: &AccountInfo, mint: &Pubkey) -> Result<String> {
+        if metadata.data_is_empty() {
+            let mut symbol = mint.to_string();
+            symbol.truncate(LP_FALLBACK_SYMBOL_LEN);
+            return Ok(symbol);
+        }
+
+        let data = metadata.try_borrow_data()?;
+        let metadata = MetadataAccount::try_deserialize(&mut &data[..])?;
+
+        // metaplex pads the symbol with zero bytes
+        Ok(metadata.symbol.trim_end_matches('\0').to_string())
+    }
+}
+
+/// Handler function for creating the Metaplex metadata of the LP mint
+pub fn create_lp_metadata_handler(ctx: Context<CreateLpMetadata>, uri: String) -> Result<()> {
+    // validate inputs
+    let name = ctx.accounts.validate(&uri)?;
+
+    let (seed, bump) = {
+        let config = ctx.accounts.config.load()?;
+        (config.seed.to_le_bytes(), config.config_bump)
+    };
+    let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let accounts = &ctx.accounts;
+    let cpi_ctx = CpiContext::new_with_signer(
+        accounts.token_metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: accounts.metadata.to_account_info(),
+            mint: accounts.lp_mint.to_account_info(),
+            mint_authority: accounts.config.to_account_info(),
+            payer: accounts.payer.to_account_info(),
+            update_authority: accounts.config.to_account_info(),
+            system_program: accounts.system_program.to_account_info(),
+            rent: accounts.rent.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    let data = DataV2 {
+        name,
+        symbol: LP_SYMBOL.to_string(),
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+
+    // mutable so the config can update it later, signed by the config as update authority
+    create_metadata_accounts_v3(cpi_ctx, data, true, true, None)
+}
@@ -1,4 +1,9 @@
-use crate::{constants::*, error::AmmError, state::Config};
+use crate::{
+    constants::*,
+    error::AmmError,
+    native_sol::{close_temp_wsol, is_native, open_temp_wsol},
+    state::Config,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -13,47 +18,49 @@ pub struct Deposit<'info> {
 
     /// AMM config account
     #[account(
-        seeds = [CONFIG_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
-        bump = config.config_bump,
-        constraint = !config.locked @ AmmError::PoolLocked,
-    )]
-    pub config: Account<'info, Config>,
-
-    /// First token mint
-    #[account(
-        constraint = mint_x.key() == config.mint_x @ AmmError::InvalidToken
-    )]
-    pub mint_x: Account<'info, Mint>,
-
-    /// Second token mint
-    #[account(
-        constraint = mint_y.key() == config.mint_y @ AmmError::InvalidToken
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_DEPOSIT) @ AmmError::DepositsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
     )]
-    pub mint_y: Account<'info, Mint>,
+    pub config: AccountLoader<'info, Config>,
 
     /// LP token mint
     #[account(
         mut,
-        seeds = [LP_MINT_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
-        bump = config.lp_bump,
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
     )]
     pub lp_mint: Account<'info, Mint>,
 
-    /// User's token X account
+    /// User's token X account, may be omitted when X is paid from `wsol_temp`
     #[account(
         mut,
-        associated_token::mint = mint_x,
+        associated_token::mint = config.load()?.mint_x,
         associated_token::authority = user
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: Option<Account<'info, TokenAccount>>,
 
-    /// User's token Y account
+    /// User's token Y account, may be omitted when Y is paid from `wsol_temp`
     #[account(
         mut,
-        associated_token::mint = mint_y,
+        associated_token::mint = config.load()?.mint_y,
         associated_token::authority = user
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: Option<Account<'info, TokenAccount>>,
+
+    /// Temporary wSOL account, pass it to pay the native side with SOL instead of wSOL
+    /// CHECK: created and closed within the instruction at this PDA
+    #[account(
+        mut,
+        seeds = [WSOL_TEMP_SEED.as_bytes(), user.key().as_ref()],
+        bump,
+    )]
+    pub wsol_temp: Option<UncheckedAccount<'info>>,
+
+    /// Native mint, required with `wsol_temp`
+    #[account(constraint = is_native(&native_mint.key()) @ AmmError::InvalidToken)]
+    pub native_mint: Option<Account<'info, Mint>>,
 
     /// User's LP token account
     #[account(
@@ -67,9 +74,9 @@ pub struct Deposit<'info> {
     /// Vault for token X
     #[account(
         mut,
-        seeds = [VAULT_X_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
-        token::mint = mint_x,
+        token::mint = config.load()?.mint_x,
         token::authority = config
     )]
     pub vault_x: Account<'info, TokenAccount>,
@@ -77,9 +84,9 @@ pub struct Deposit<'info> {
     /// Vault for token Y
     #[account(
         mut,
-        seeds = [VAULT_Y_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
-        token::mint = mint_y,
+        token::mint = config.load()?.mint_y,
         token::authority = config
     )]
     pub vault_y: Account<'info, TokenAccount>,
@@ -96,20 +103,32 @@ pub struct Deposit<'info> {
 
 impl<'info> Deposit<'info> {
     /// Validate deposit parameters
-    pub fn validate(&self, amount_x: u64, amount_y: u64, min_lp: u64) -> Result<()> {
+    pub fn validate(&self, max_x: u64, max_y: u64, min_lp: u64) -> Result<()> {
         // check amounts are positive
-        require!(amount_x > 0 && amount_y > 0, AmmError::InvalidAmount);
+        require!(max_x > 0 && max_y > 0, AmmError::InvalidAmount);
         require!(min_lp > 0, AmmError::LiquidityLessThanMinimum);
 
-        // check user has sufficient balance
-        require!(
-            self.user_x.amount >= amount_x,
-            AmmError::InsufficientBalance
-        );
-        require!(
-            self.user_y.amount >= amount_y,
-            AmmError::InsufficientBalance
-        );
+        Ok(())
+    }
+
+    /// Check user has sufficient balance for the amounts actually deposited
+    pub fn validate_balances(&self, amount_x: u64, amount_y: u64) -> Result<()> {
+        let (mint_x, mint_y) = {
+            let config = self.config.load()?;
+            (config.mint_x, config.mint_y)
+        };
+
+        for (mint, user_account, amount) in [
+            (mint_x, &self.user_x, amount_x),
+            (mint_y, &self.user_y, amount_y),
+        ] {
+            // the temporary wSOL account is funded with exactly the amount deposited
+            if self.uses_temp_wsol(&mint) {
+                continue;
+            }
+            let user_account = user_account.as_ref().ok_or(AmmError::MissingTokenAccount)?;
+            require!(user_account.amount >= amount, AmmError::InsufficientBalance);
+        }
 
         Ok(())
     }
@@ -138,8 +157,14 @@ impl<'info> Deposit<'info> {
             .ok_or(AmmError::Underflow)?)
     }
 
-    /// Calculate LP tokens for subsequent deposits
-    pub fn calculate_subsequent_deposit_lp(&self, amount_x: u64, amount_y: u64) -> Result<u64> {
+    /// Calculate LP tokens and the exact token amounts for subsequent deposits
+    ///
+    /// Returns `(lp_amount, amount_x, amount_y)` where the amounts match the
+    /// pool ratio and never exceed `max_x` / `max_y`.
+    ///
+    /// The handler checks the amounts against `min_x` / `min_y`, since the
+    /// ratio can move before the deposit lands.
+    pub fn calculate_subsequent_deposit(&self, max_x: u64, max_y: u64) -> Result<(u64, u64, u64)> {
         let reserve_x = self.vault_x.amount;
         let reserve_y = self.vault_y.amount;
         let total_supply = self.lp_mint.supply;
@@ -148,33 +173,119 @@ impl<'info> Deposit<'info> {
         require!(total_supply > 0, AmmError::ZeroBalance);
 
         // calculate LP based on the minimum ratio to maintain pool balance
-        let lp_from_x = (amount_x as u128)
+        let lp_from_x = (max_x as u128)
             .checked_mul(total_supply as u128)
             .ok_or(AmmError::Overflow)?
             .checked_div(reserve_x as u128)
             .ok_or(AmmError::ZeroBalance)?;
 
-        let lp_from_y = (amount_y as u128)
+        let lp_from_y = (max_y as u128)
             .checked_mul(total_supply as u128)
             .ok_or(AmmError::Overflow)?
             .checked_div(reserve_y as u128)
             .ok_or(AmmError::ZeroBalance)?;
 
         // take the minimum to maintain pool ratio
-        let lp_amount = std::cmp::min(lp_from_x, lp_from_y) as u64;
+        let lp_amount = std::cmp::min(lp_from_x, lp_from_y);
 
         require!(lp_amount > 0, AmmError::LiquidityLessThanMinimum);
 
-        Ok(lp_amount)
+        // tokens required for exactly `lp_amount`, rounded up in favour of the pool
+        let amount_x = Self::amount_for_lp(lp_amount, reserve_x, total_supply)?;
+        let amount_y = Self::amount_for_lp(lp_amount, reserve_y, total_supply)?;
+
+        Ok((
+            u64::try_from(lp_amount).map_err(|_| AmmError::Overflow)?,
+            amount_x,
+            amount_y,
+        ))
+    }
+
+    /// Token amount backing `lp_amount`: ceil(lp_amount * reserve / total_supply)
+    fn amount_for_lp(lp_amount: u128, reserve: u64, total_supply: u64) -> Result<u64> {
+        let amount = lp_amount
+            .checked_mul(reserve as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_add(total_supply as u128 - 1)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(total_supply as u128)
+            .ok_or(AmmError::ZeroBalance)?;
+
+        Ok(u64::try_from(amount).map_err(|_| AmmError::Overflow)?)
+    }
+
+    /// Whether `mint` is paid from the temporary wSOL account
+    fn uses_temp_wsol(&self, mint: &Pubkey) -> bool {
+        self.wsol_temp.is_some() && is_native(mint)
+    }
+
+    /// Account paying in `mint`, the temporary wSOL account on the native side when given
+    fn source(
+        &self,
+        mint: &Pubkey,
+        user_account: &Option<Account<'info, TokenAccount>>,
+    ) -> Result<AccountInfo<'info>> {
+        match (&self.wsol_temp, user_account) {
+            (Some(wsol_temp), _) if is_native(mint) => Ok(wsol_temp.to_account_info()),
+            (_, Some(user_account)) => Ok(user_account.to_account_info()),
+            _ => err!(AmmError::MissingTokenAccount),
+        }
+    }
+
+    /// Wrap the native side's amount into the temporary wSOL account, if given
+    pub fn wrap_native(&self, amount_x: u64, amount_y: u64, bump: Option<u8>) -> Result<()> {
+        let (Some(wsol_temp), Some(bump)) = (&self.wsol_temp, bump) else {
+            return Ok(());
+        };
+
+        let amount = if is_native(&self.config.load()?.mint_x) {
+            amount_x
+        } else if is_native(&self.config.load()?.mint_y) {
+            amount_y
+        } else {
+            return err!(AmmError::InvalidToken);
+        };
+        let native_mint = self
+            .native_mint
+            .as_ref()
+            .ok_or(AmmError::MissingTokenAccount)?;
+
+        open_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            bump,
+            &native_mint.to_account_info(),
+            amount,
+            &self.system_program.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+
+    /// Close the temporary wSOL account, if given, returning its rent
+    pub fn unwrap_native(&self) -> Result<()> {
+        let Some(wsol_temp) = &self.wsol_temp else {
+            return Ok(());
+        };
+
+        close_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
     }
 
     /// Transfer tokens from user to vaults
     pub fn transfer_to_vaults(&self, amount_x: u64, amount_y: u64) -> Result<()> {
+        let (mint_x, mint_y) = {
+            let config = self.config.load()?;
+            (config.mint_x, config.mint_y)
+        };
+
         // transfer token X to vault
         let transfer_x_ctx = CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.user_x.to_account_info(),
+                from: self.source(&mint_x, &self.user_x)?,
                 to: self.vault_x.to_account_info(),
                 authority: self.user.to_account_info(),
             },
@@ -185,7 +296,7 @@ impl<'info> Deposit<'info> {
         let transfer_y_ctx = CpiContext::new(
             self.token_program.to_account_info(),
             Transfer {
-                from: self.user_y.to_account_info(),
+                from: self.source(&mint_y, &self.user_y)?,
                 to: self.vault_y.to_account_info(),
                 authority: self.user.to_account_info(),
             },
@@ -197,11 +308,8 @@ impl<'info> Deposit<'info> {
 
     /// Mint LP tokens to user
     pub fn mint_lp_tokens(&self, lp_amount: u64, config_bump: u8) -> Result<()> {
-        let seeds = &[
-            CONFIG_SEED.as_bytes(),
-            &self.config.seed.to_le_bytes(),
-            &[config_bump],
-        ];
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
         let signer_seeds = &[&seeds[..]];
 
         let mint_ctx = CpiContext::new_with_signer(
@@ -223,34 +331,48 @@ impl<'info> Deposit<'info> {
 /// Handler function for Depositing liquidity pool
 pub fn deposit_handler(
     ctx: Context<Deposit>,
-    amount_x: u64,
-    amount_y: u64,
+    max_x: u64,
+    max_y: u64,
     min_lp: u64,
+    min_x: u64,
+    min_y: u64,
 ) -> Result<()> {
     // validate inputs
-    ctx.accounts.validate(amount_x, amount_y, min_lp)?;
+    ctx.accounts.validate(max_x, max_y, min_lp)?;
 
-    let config_bump = ctx.accounts.config.config_bump;
+    let config_bump = ctx.accounts.config.load()?.config_bump;
 
-    // calculate LP tokens based on deposit type
-    let lp_amount = if ctx.accounts.is_first_deposit() {
+    // calculate LP tokens and token amounts based on deposit type
+    let (lp_amount, amount_x, amount_y) = if ctx.accounts.is_first_deposit() {
         msg!("First deposit detected");
-        ctx.accounts
-            .calculate_first_deposit_lp(amount_x, amount_y)?
+        // the first deposit sets the price, so both amounts are used in full
+        let lp_amount = ctx.accounts.calculate_first_deposit_lp(max_x, max_y)?;
+        (lp_amount, max_x, max_y)
     } else {
         msg!("Subsequent deposit detected");
-        ctx.accounts
-            .calculate_subsequent_deposit_lp(amount_x, amount_y)?
+        ctx.accounts.calculate_subsequent_deposit(max_x, max_y)?
     };
 
-    // check slippage protection
+    // check slippage protection, the amounts never exceed the maximums by construction
     require!(lp_amount >= min_lp, AmmError::SlippageExceeded);
+    require!(
+        amount_x >= min_x && amount_y >= min_y,
+        AmmError::SlippageExceeded
+    );
 
-    // transfer tokens to vaults
+    // check user can cover the matched amounts, then wrap native SOL when opted in
+    ctx.accounts.validate_balances(amount_x, amount_y)?;
+    ctx.accounts
+        .wrap_native(amount_x, amount_y, ctx.bumps.wsol_temp)?;
+
+    // transfer only the tokens matching the pool ratio
     ctx.accounts.transfer_to_vaults(amount_x, amount_y)?;
 
     // mint LP tokens to user
     ctx.accounts.mint_lp_tokens(lp_amount, config_bump)?;
 
+    // close the temporary wSOL account, if any
+    ctx.accounts.unwrap_native()?;
+
     Ok(())
 }
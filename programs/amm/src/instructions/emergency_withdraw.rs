@@ -0,0 +1,217 @@
+use crate::{constants::*, error::AmmError, events::EmergencyWithdrawal, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    /// User withdrawing liquidity
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// First token mint
+    #[account(
+        constraint = mint_x.key() == config.load()?.mint_x @ AmmError::InvalidToken
+    )]
+    pub mint_x: Account<'info, Mint>,
+
+    /// Second token mint
+    #[account(
+        constraint = mint_y.key() == config.load()?.mint_y @ AmmError::InvalidToken
+    )]
+    pub mint_y: Account<'info, Mint>,
+
+    /// LP token mint
+    #[account(
+        mut,
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// User's token X account, created if the LP closed it
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user
+    )]
+    pub user_x: Account<'info, TokenAccount>,
+
+    /// User's token Y account, created if the LP closed it
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user
+    )]
+    pub user_y: Account<'info, TokenAccount>,
+
+    /// User's LP token account
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint_x,
+        token::authority = config,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint_y,
+        token::authority = config,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> EmergencyWithdraw<'info> {
+    /// Validate emergency withdraw parameters
+    pub fn validate(&self, lp_amount: u64, slot: u64) -> Result<()> {
+        // only once withdrawals have been paused past the grace period
+        require!(
+            self.config.load()?.can_emergency_withdraw(slot),
+            AmmError::EmergencyWithdrawUnavailable
+        );
+
+        // check lp amount is positive
+        require!(lp_amount > 0, AmmError::InvalidAmount);
+
+        // check user has sufficient LP tokens
+        require!(
+            self.user_lp.amount >= lp_amount,
+            AmmError::InsufficientBalance
+        );
+        require!(self.lp_mint.supply > 0, AmmError::ZeroBalance);
+
+        Ok(())
+    }
+
+    /// Calculate the user's pro-rata share of both vaults
+    pub fn calculate_withdraw_amounts(&self, lp_amount: u64) -> Result<(u64, u64)> {
+        let total_supply = self.lp_mint.supply as u128;
+
+        let amount_x = (lp_amount as u128)
+            .checked_mul(self.vault_x.amount as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(total_supply)
+            .ok_or(AmmError::ZeroBalance)? as u64;
+
+        let amount_y = (lp_amount as u128)
+            .checked_mul(self.vault_y.amount as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(total_supply)
+            .ok_or(AmmError::ZeroBalance)? as u64;
+
+        // a drained side must not block the exit from the other one
+        require!(
+            amount_x > 0 || amount_y > 0,
+            AmmError::LiquidityLessThanMinimum
+        );
+
+        Ok((amount_x, amount_y))
+    }
+
+    /// Transfer a token amount from a vault to the user
+    pub fn transfer_from_vault(
+        &self,
+        vault: &Account<'info, TokenAccount>,
+        to: &Account<'info, TokenAccount>,
+        amount: u64,
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let (seed, bump) = {
+            let config = self.config.load()?;
+            (config.seed.to_le_bytes(), config.config_bump)
+        };
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to: to.to_account_info(),
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token::transfer(transfer_ctx, amount)
+    }
+
+    /// Burn LP tokens from user
+    pub fn burn_lp_tokens(&self, lp_amount: u64) -> Result<()> {
+        let burn_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            token::Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.user_lp.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        );
+
+        token::burn(burn_ctx, lp_amount)
+    }
+}
+
+/// Handler function for redeeming LP tokens while withdrawals are stuck paused
+pub fn emergency_withdraw_handler(ctx: Context<EmergencyWithdraw>, lp_amount: u64) -> Result<()> {
+    let slot = Clock::get()?.slot;
+
+    // validate inputs
+    ctx.accounts.validate(lp_amount, slot)?;
+
+    // plain pro-rata redemption, no curve and no fees
+    let (amount_x, amount_y) = ctx.accounts.calculate_withdraw_amounts(lp_amount)?;
+
+    // burn LP tokens first
+    ctx.accounts.burn_lp_tokens(lp_amount)?;
+
+    let accounts = &ctx.accounts;
+    accounts.transfer_from_vault(&accounts.vault_x, &accounts.user_x, amount_x)?;
+    accounts.transfer_from_vault(&accounts.vault_y, &accounts.user_y, amount_y)?;
+
+    emit!(EmergencyWithdrawal {
+        seed: accounts.config.load()?.seed,
+        user: accounts.user.key(),
+        lp_amount,
+        amount_x,
+        amount_y,
+    });
+
+    Ok(())
+}
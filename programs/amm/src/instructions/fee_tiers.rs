@@ -0,0 +1,39 @@
+use crate::{constants::*, state::Factory};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ManageFeeTiers<'info> {
+    /// Global admin
+    pub admin: Signer<'info>,
+
+    /// The factory account holding the fee tiers
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED.as_bytes()],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+}
+
+impl<'info> ManageFeeTiers<'info> {
+    /// Validate the caller is the global admin
+    pub fn validate(&self) -> Result<()> {
+        self.factory.can_modify(&self.admin.key())
+    }
+}
+
+/// Handler function for whitelisting a fee tier
+pub fn add_fee_tier_handler(ctx: Context<ManageFeeTiers>, fee: u16) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+
+    ctx.accounts.factory.add_fee_tier(fee)
+}
+
+/// Handler function for removing a whitelisted fee tier
+pub fn remove_fee_tier_handler(ctx: Context<ManageFeeTiers>, fee: u16) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+
+    ctx.accounts.factory.remove_fee_tier(fee)
+}
@@ -0,0 +1,220 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+    },
+};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    /// User taking the flash loan
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_FLASH_LOAN) @ AmmError::FlashLoansPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_x,
+        token::authority = config,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_y,
+        token::authority = config,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// Token account receiving the borrowed X
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_x,
+    )]
+    pub receiver_x: Account<'info, TokenAccount>,
+
+    /// Token account receiving the borrowed Y
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_y,
+    )]
+    pub receiver_y: Account<'info, TokenAccount>,
+
+    /// Program invoked with the borrowed funds, must repay before returning
+    /// CHECK: arbitrary receiver program, repayment is verified on the vault balances
+    #[account(
+        executable,
+        constraint = receiver_program.key() != crate::ID @ AmmError::InvalidFlashLoanReceiver,
+    )]
+    pub receiver_program: UncheckedAccount<'info>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FlashLoan<'info> {
+    /// Validate flash loan parameters
+    pub fn validate(&self, amount_x: u64, amount_y: u64) -> Result<()> {
+        // at least one side must be borrowed
+        require!(amount_x > 0 || amount_y > 0, AmmError::InvalidAmount);
+
+        // pool must hold the borrowed amounts
+        require!(
+            self.vault_x.amount >= amount_x && self.vault_y.amount >= amount_y,
+            AmmError::InsufficientBalance
+        );
+
+        Ok(())
+    }
+
+    /// Flash fee for a borrowed amount, rounded up
+    pub fn calculate_fee(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(FLASH_LOAN_FEE_BASIS_POINTS as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_add(FEE_BASIS_POINTS as u128 - 1)
+            .ok_or(AmmError::Overflow)?
+            / FEE_BASIS_POINTS as u128;
+
+        Ok(u64::try_from(fee).map_err(|_| AmmError::Overflow)?)
+    }
+
+    /// Transfer borrowed tokens from vaults to the receiver accounts
+    pub fn lend(&self, amount_x: u64, amount_y: u64, config_bump: u8) -> Result<()> {
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        if amount_x > 0 {
+            let transfer_x_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault_x.to_account_info(),
+                    to: self.receiver_x.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_x_ctx, amount_x)?;
+        }
+
+        if amount_y > 0 {
+            let transfer_y_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault_y.to_account_info(),
+                    to: self.receiver_y.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_y_ctx, amount_y)?;
+        }
+
+        Ok(())
+    }
+
+    /// Invoke the receiver program with the remaining accounts
+    pub fn invoke_receiver(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+        data: Vec<u8>,
+    ) -> Result<()> {
+        invoke_flash_receiver(&self.receiver_program, remaining_accounts, data)
+    }
+}
+
+/// Invoke a flash receiver program, forwarding the remaining accounts as-is
+pub(crate) fn invoke_flash_receiver<'info>(
+    receiver_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<()> {
+    let accounts = remaining_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+
+    let instruction = Instruction {
+        program_id: receiver_program.key(),
+        accounts,
+        data,
+    };
+
+    let mut account_infos = remaining_accounts.to_vec();
+    account_infos.push(receiver_program.clone());
+
+    invoke(&instruction, &account_infos)?;
+
+    Ok(())
+}
+
+pub fn flash_loan_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+    amount_x: u64,
+    amount_y: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(amount_x, amount_y)?;
+
+    let config_bump = ctx.accounts.config.load()?.config_bump;
+
+    // balances the vaults must return to, plus the flash fee for LPs
+    let required_x = ctx
+        .accounts
+        .vault_x
+        .amount
+        .checked_add(ctx.accounts.calculate_fee(amount_x)?)
+        .ok_or(AmmError::Overflow)?;
+    let required_y = ctx
+        .accounts
+        .vault_y
+        .amount
+        .checked_add(ctx.accounts.calculate_fee(amount_y)?)
+        .ok_or(AmmError::Overflow)?;
+
+    // set the reentrancy guard before handing out control, zero-copy writes land
+    // in the account data directly so deposit/withdraw/swap reject the receiver
+    ctx.accounts.config.load_mut()?.flash_loan_active = 1;
+
+    // 1. vault -> receiver
+    ctx.accounts.lend(amount_x, amount_y, config_bump)?;
+
+    // 2. receiver uses and repays the funds
+    ctx.accounts.invoke_receiver(ctx.remaining_accounts, data)?;
+
+    // 3. verify repayment plus fee
+    ctx.accounts.vault_x.reload()?;
+    ctx.accounts.vault_y.reload()?;
+    require!(
+        ctx.accounts.vault_x.amount >= required_x && ctx.accounts.vault_y.amount >= required_y,
+        AmmError::FlashLoanNotRepaid
+    );
+
+    // release the guard
+    ctx.accounts.config.load_mut()?.flash_loan_active = 0;
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use crate::{
+    constants::*, error::AmmError, instructions::flash_loan::invoke_flash_receiver, state::Config,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use u256::U256;
+
+// kept out of scope of the anchor prelude, whose `Result` alias clashes with the macro
+#[allow(clippy::manual_div_ceil)]
+mod u256 {
+    uint::construct_uint! {
+        /// 256-bit unsigned integer for the constant product invariant check
+        pub struct U256(4);
+    }
+}
+
+#[derive(Accounts)]
+pub struct FlashSwap<'info> {
+    /// User performing the flash swap
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_SWAP) @ AmmError::SwapsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_x,
+        token::authority = config,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_y,
+        token::authority = config,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// Token account receiving the X output
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_x,
+    )]
+    pub receiver_x: Account<'info, TokenAccount>,
+
+    /// Token account receiving the Y output
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_y,
+    )]
+    pub receiver_y: Account<'info, TokenAccount>,
+
+    /// Callback program invoked after the optimistic transfer
+    /// CHECK: arbitrary callback program, payment is verified on the vault balances
+    #[account(
+        executable,
+        constraint = callback_program.key() != crate::ID @ AmmError::InvalidFlashLoanReceiver,
+    )]
+    pub callback_program: UncheckedAccount<'info>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FlashSwap<'info> {
+    /// Validate flash swap parameters
+    pub fn validate(&self, amount_x_out: u64, amount_y_out: u64) -> Result<()> {
+        // at least one side must be sent out
+        require!(
+            amount_x_out > 0 || amount_y_out > 0,
+            AmmError::InvalidAmount
+        );
+
+        // outputs must leave liquidity in both vaults
+        require!(
+            amount_x_out < self.vault_x.amount && amount_y_out < self.vault_y.amount,
+            AmmError::InsufficientBalance
+        );
+
+        Ok(())
+    }
+
+    /// Transfer outputs from vaults to the receiver accounts before payment
+    pub fn transfer_out(
+        &self,
+        amount_x_out: u64,
+        amount_y_out: u64,
+        config_bump: u8,
+    ) -> Result<()> {
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        if amount_x_out > 0 {
+            let transfer_x_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault_x.to_account_info(),
+                    to: self.receiver_x.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_x_ctx, amount_x_out)?;
+        }
+
+        if amount_y_out > 0 {
+            let transfer_y_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.vault_y.to_account_info(),
+                    to: self.receiver_y.to_account_info(),
+                    authority: self.config.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(transfer_y_ctx, amount_y_out)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check the constant product invariant on the final vault balances
+    ///
+    /// The fee is charged on whatever was paid in on either side:
+    /// (x * 10000 - x_in * fee) * (y * 10000 - y_in * fee) >= x0 * y0 * 10000^2
+    pub fn check_invariant(
+        &self,
+        reserves: (u64, u64),
+        amounts_out: (u64, u64),
+        fee: u16,
+    ) -> Result<()> {
+        let (reserve_x, reserve_y) = reserves;
+        let (amount_x_out, amount_y_out) = amounts_out;
+        let balance_x = self.vault_x.amount;
+        let balance_y = self.vault_y.amount;
+
+        // amounts paid in beyond what was left after the optimistic transfer
+        let amount_x_in = balance_x.saturating_sub(reserve_x - amount_x_out);
+        let amount_y_in = balance_y.saturating_sub(reserve_y - amount_y_out);
+        require!(
+            amount_x_in > 0 || amount_y_in > 0,
+            AmmError::InsufficientBalance
+        );
+
+        let denom_bps = U256::from(FEE_BASIS_POINTS as u64);
+        let fee_bps = U256::from(fee);
+
+        let adjusted_x = U256::from(balance_x) * denom_bps - U256::from(amount_x_in) * fee_bps;
+        let adjusted_y = U256::from(balance_y) * denom_bps - U256::from(amount_y_in) * fee_bps;
+
+        let k_before = U256::from(reserve_x) * U256::from(reserve_y) * denom_bps * denom_bps;
+
+        require!(
+            adjusted_x * adjusted_y >= k_before,
+            AmmError::SlippageExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Net trade of the settled flash swap as `(is_x_to_y, amount_in, amount_out)`,
+    /// `None` when neither vault ends below its starting reserve
+    pub fn net_swap(&self, reserves: (u64, u64)) -> Option<(bool, u64, u64)> {
+        let (reserve_x, reserve_y) = reserves;
+        let balance_x = self.vault_x.amount;
+        let balance_y = self.vault_y.amount;
+
+        // the invariant check rules out both vaults ending lower
+        if balance_y < reserve_y {
+            Some((
+                true,
+                balance_x.saturating_sub(reserve_x),
+                reserve_y - balance_y,
+            ))
+        } else if balance_x < reserve_x {
+            Some((
+                false,
+                balance_y.saturating_sub(reserve_y),
+                reserve_x - balance_x,
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+pub fn flash_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, FlashSwap<'info>>,
+    amount_x_out: u64,
+    amount_y_out: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(amount_x_out, amount_y_out)?;
+
+    let reserves = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    let (config_bump, fee) = {
+        let mut config = ctx.accounts.config.load_mut()?;
+
+        // set the reentrancy guard before handing out control
+        config.flash_loan_active = 1;
+        (
+            config.config_bump,
+            config.swap_fee(Clock::get()?.unix_timestamp),
+        )
+    };
+
+    // 1. vault -> receiver (optimistic)
+    ctx.accounts
+        .transfer_out(amount_x_out, amount_y_out, config_bump)?;
+
+    // 2. callback pays in either token
+    invoke_flash_receiver(&ctx.accounts.callback_program, ctx.remaining_accounts, data)?;
+
+    // 3. verify x * y = k (with fee) on the final balances
+    ctx.accounts.vault_x.reload()?;
+    ctx.accounts.vault_y.reload()?;
+    ctx.accounts
+        .check_invariant(reserves, (amount_x_out, amount_y_out), fee)?;
+
+    // 4. the net trade is bound by the same pool-level limits as a regular swap
+    if let Some((is_x_to_y, amount_in, amount_out)) = ctx.accounts.net_swap(reserves) {
+        let (reserve_in, reserve_out) = Config::reserves_in_out(is_x_to_y, reserves.0, reserves.1);
+        ctx.accounts.config.load()?.check_swap_limits(
+            amount_in,
+            amount_out,
+            reserve_in,
+            reserve_out,
+        )?;
+    }
+
+    // release the guard and track the new price for the dynamic fee
+    let after = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.flash_loan_active = 0;
+    config.record_swap(reserves, after)?;
+
+    Ok(())
+}
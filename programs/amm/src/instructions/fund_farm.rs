@@ -0,0 +1,79 @@
+use crate::{constants::*, error::AmmError, state::Farm};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct FundFarm<'info> {
+    /// Reward authority of the farm
+    pub reward_authority: Signer<'info>,
+
+    /// Farm to fund
+    #[account(
+        mut,
+        seeds = [FARM_SEED.as_bytes(), farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+        has_one = reward_authority @ AmmError::Unauthorized,
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// Reward authority's token account the rewards are taken from
+    #[account(
+        mut,
+        token::mint = farm.reward_mint,
+    )]
+    pub funder: Account<'info, TokenAccount>,
+
+    /// Vault holding the funded rewards
+    #[account(
+        mut,
+        seeds = [FARM_REWARD_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundFarm<'info> {
+    /// Validate funding parameters
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        // check amount is positive
+        require!(amount > 0, AmmError::InvalidAmount);
+
+        Ok(())
+    }
+
+    /// Transfer rewards from the funder to the reward vault
+    pub fn transfer_rewards(&self, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.funder.to_account_info(),
+                to: self.reward_vault.to_account_info(),
+                authority: self.reward_authority.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+/// Handler function for adding rewards to a farm
+pub fn fund_farm_handler(ctx: Context<FundFarm>, amount: u64) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(amount)?;
+
+    // accrue first so an unfunded stretch is not paid out retroactively
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.farm.update(now)?;
+
+    ctx.accounts.transfer_rewards(amount)?;
+
+    let farm = &mut ctx.accounts.farm;
+    farm.undistributed = farm
+        .undistributed
+        .checked_add(amount)
+        .ok_or(AmmError::Overflow)?;
+
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use crate::{constants::*, error::AmmError, state::Config};
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{CanonicalPool, CircuitBreaker, Config, DynamicFee, Factory, PoolIndex},
+};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -12,15 +16,23 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    /// Global factory that registers the pool
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED.as_bytes()],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+
     /// The AMM config account to be created
     #[account(
         init,
         payer = authority,
-        space = 8 + Config::INIT_SPACE,
+        space = 8 + Config::LEN,
         seeds = [CONFIG_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
         bump
     )]
-    pub config: Account<'info, Config>,
+    pub config: AccountLoader<'info, Config>,
 
     /// First token mint for the pool
     pub mint_x: Account<'info, Mint>,
@@ -28,11 +40,11 @@ pub struct Initialize<'info> {
     /// Second token mint for the pool
     pub mint_y: Account<'info, Mint>,
 
-    /// LP token mint to be created
+    /// LP token mint to be created, its decimals are derived from the pair
     #[account(
         init,
         payer = authority,
-        mint::decimals = 6,
+        mint::decimals = Config::lp_decimals(mint_x.decimals, mint_y.decimals),
         mint::authority = config,
         seeds = [LP_MINT_SEED.as_bytes(), seed.to_le_bytes().as_ref()],
         bump
@@ -61,6 +73,31 @@ pub struct Initialize<'info> {
     )]
     pub vault_y: Account<'info, anchor_spl::token::TokenAccount>,
 
+    /// Optional canonical pool entry for the sorted pair and fee tier
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CanonicalPool::INIT_SPACE,
+        seeds = [
+            CANONICAL_POOL_SEED.as_bytes(),
+            mint_x.key().as_ref(),
+            mint_y.key().as_ref(),
+            fee.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub canonical_pool: Option<Account<'info, CanonicalPool>>,
+
+    /// Registry entry for the new pool, at the factory's current `pool_count`
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PoolIndex::INIT_SPACE,
+        seeds = [POOL_INDEX_SEED.as_bytes(), factory.pool_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pool_index: Account<'info, PoolIndex>,
+
     /// SPL token program
     pub token_program: Program<'info, Token>,
 
@@ -80,6 +117,12 @@ impl<'info> Initialize<'info> {
             AmmError::InvalidFee
         );
 
+        // only governance-approved fee tiers can be used
+        require!(
+            self.factory.is_fee_tier_allowed(fee),
+            AmmError::FeeTierNotAllowed
+        );
+
         // ensure mint X and Y are different
         require!(
             self.mint_x.key() != self.mint_y.key(),
@@ -90,6 +133,14 @@ impl<'info> Initialize<'info> {
         require!(self.mint_x.decimals <= 9, AmmError::InvalidPrecision);
         require!(self.mint_y.decimals <= 9, AmmError::InvalidPrecision);
 
+        // canonical pools must be created with sorted mints
+        if self.canonical_pool.is_some() {
+            require!(
+                self.mint_x.key() < self.mint_y.key(),
+                AmmError::InvalidMintOrder
+            );
+        }
+
         Ok(())
     }
 }
@@ -104,15 +155,46 @@ pub fn initialize_handler(ctx: Context<Initialize>, seed: u64, fee: u16) -> Resu
     let lp_bump = ctx.bumps.lp_mint;
 
     // initialize config account
-    let config = &mut ctx.accounts.config;
+    let mut config = ctx.accounts.config.load_init()?;
+    config.version = CONFIG_VERSION;
     config.seed = seed;
-    config.authority = Some(ctx.accounts.authority.key());
+    config.authority = ctx.accounts.authority.key();
     config.mint_x = ctx.accounts.mint_x.key();
     config.mint_y = ctx.accounts.mint_y.key();
     config.fee = fee;
-    config.locked = false; // pool starts unlocked
+    config.dynamic_fee = DynamicFee::default(); // static fee until enabled
+    config.max_price_impact = 0; // no swap limits until set
+    config.max_trade_size = 0;
+    config.circuit_breaker = CircuitBreaker::default(); // disarmed until set
+    config.paused = 0; // pool starts with every operation enabled
+    config.withdraw_paused_slot = 0;
+    config.withdraw_resumed_slot = 0;
+    config.flash_loan_active = 0;
+    config.canonical = ctx.accounts.canonical_pool.is_some() as u8;
     config.config_bump = config_bump;
     config.lp_bump = lp_bump;
+    config.reserved = [0; CONFIG_RESERVED_SPACE];
+    config.padding = [0; 4];
+    drop(config);
+
+    // register as the canonical pool for this pair and fee tier
+    if let Some(canonical_pool) = ctx.accounts.canonical_pool.as_mut() {
+        canonical_pool.config = ctx.accounts.config.key();
+        canonical_pool.seed = seed;
+        canonical_pool.bump = ctx.bumps.canonical_pool.ok_or(AmmError::BumpError)?;
+    }
+
+    // register the pool in the factory
+    let factory = &mut ctx.accounts.factory;
+    let pool_index = &mut ctx.accounts.pool_index;
+    pool_index.index = factory.pool_count;
+    pool_index.config = ctx.accounts.config.key();
+    pool_index.bump = ctx.bumps.pool_index;
+
+    factory.pool_count = factory
+        .pool_count
+        .checked_add(1)
+        .ok_or(AmmError::Overflow)?;
 
     Ok(())
 }
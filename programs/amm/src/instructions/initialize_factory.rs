@@ -0,0 +1,61 @@
+use crate::{constants::*, error::AmmError, state::Factory};
+use anchor_lang::{prelude::*, solana_program::bpf_loader_upgradeable};
+
+#[derive(Accounts)]
+pub struct InitializeFactory<'info> {
+    /// Global admin creating the factory, must be the program's upgrade authority
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Program data account of this program, holding its upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ AmmError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The factory account to be created
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Factory::INIT_SPACE,
+        seeds = [FACTORY_SEED.as_bytes()],
+        bump
+    )]
+    pub factory: Account<'info, Factory>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeFactory<'info> {
+    /// Validate the factory parameters
+    pub fn validate(&self, protocol_fee: u16, fee_tiers: &[u16]) -> Result<()> {
+        Factory::validate_protocol_fee(protocol_fee)?;
+        Factory::validate_fee_tiers(fee_tiers)?;
+
+        Ok(())
+    }
+}
+
+/// Handler function for creating the global factory
+pub fn initialize_factory_handler(
+    ctx: Context<InitializeFactory>,
+    protocol_fee: u16,
+    fee_tiers: Vec<u16>,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(protocol_fee, &fee_tiers)?;
+
+    // initialize factory account
+    let factory = &mut ctx.accounts.factory;
+    factory.admin = ctx.accounts.admin.key();
+    factory.protocol_fee = protocol_fee;
+    factory.fee_tiers = fee_tiers;
+    factory.pool_count = 0;
+    factory.bump = ctx.bumps.factory;
+
+    Ok(())
+}
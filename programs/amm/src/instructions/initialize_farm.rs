@@ -0,0 +1,102 @@
+use crate::{
+    constants::*,
+    state::{Config, Farm},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct InitializeFarm<'info> {
+    /// Pool authority creating the farm
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// AMM config account of the pool
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// LP token mint of the pool, staked in the farm
+    #[account(
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Token paid out as rewards, one farm per pool and reward mint
+    pub reward_mint: Account<'info, Mint>,
+
+    /// The farm account to be created
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Farm::INIT_SPACE,
+        seeds = [FARM_SEED.as_bytes(), config.key().as_ref(), reward_mint.key().as_ref()],
+        bump
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// Vault holding the funded rewards
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = farm,
+        seeds = [FARM_REWARD_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// Vault holding the staked LP tokens
+    #[account(
+        init,
+        payer = authority,
+        token::mint = lp_mint,
+        token::authority = farm,
+        seeds = [FARM_LP_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeFarm<'info> {
+    /// Validate the farm parameters
+    pub fn validate(&self) -> Result<()> {
+        // only the pool authority can start a farm on its pool
+        self.config.load()?.can_modify(&self.authority.key())?;
+
+        Ok(())
+    }
+}
+
+/// Handler function for creating a liquidity mining farm on a pool
+pub fn initialize_farm_handler(
+    ctx: Context<InitializeFarm>,
+    reward_authority: Pubkey,
+    reward_rate: u64,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+
+    let farm = &mut ctx.accounts.farm;
+    farm.config = ctx.accounts.config.key();
+    farm.lp_mint = ctx.accounts.lp_mint.key();
+    farm.reward_mint = ctx.accounts.reward_mint.key();
+    farm.reward_authority = reward_authority;
+    farm.reward_rate = reward_rate;
+    farm.undistributed = 0; // emits nothing until funded
+    farm.acc_reward_per_share = 0;
+    farm.total_staked = 0;
+    farm.last_update_ts = Clock::get()?.unix_timestamp;
+    farm.bump = ctx.bumps.farm;
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    events::LpLocked,
+    state::{Config, LpLock},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct LockLp<'info> {
+    /// Owner locking LP tokens
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// LP token mint
+    #[account(
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Owner's LP token account
+    #[account(
+        mut,
+        token::mint = lp_mint,
+        token::authority = owner,
+    )]
+    pub owner_lp: Account<'info, TokenAccount>,
+
+    /// The lock account to be created
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LpLock::INIT_SPACE,
+        seeds = [LP_LOCK_SEED.as_bytes(), config.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub lock: Account<'info, LpLock>,
+
+    /// Vault escrowing the locked LP tokens
+    #[account(
+        init,
+        payer = owner,
+        token::mint = lp_mint,
+        token::authority = lock,
+        seeds = [LP_LOCK_VAULT_SEED.as_bytes(), lock.key().as_ref()],
+        bump
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> LockLp<'info> {
+    /// Validate lock parameters
+    pub fn validate(&self, amount: u64, unlock_ts: i64, now: i64) -> Result<()> {
+        // check amount is positive
+        require!(amount > 0, AmmError::InvalidAmount);
+
+        // check owner has sufficient LP tokens
+        require!(
+            self.owner_lp.amount >= amount,
+            AmmError::InsufficientBalance
+        );
+
+        // a lock that is already over proves nothing
+        require!(unlock_ts > now, AmmError::InvalidUnlockTime);
+
+        Ok(())
+    }
+
+    /// Transfer LP tokens from the owner to the lock vault
+    pub fn transfer_in(&self, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.owner_lp.to_account_info(),
+                to: self.lock_vault.to_account_info(),
+                authority: self.owner.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+/// Handler function for locking LP tokens until `unlock_ts`, optionally vesting linearly
+pub fn lock_lp_handler(
+    ctx: Context<LockLp>,
+    amount: u64,
+    unlock_ts: i64,
+    linear: bool,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // validate inputs
+    ctx.accounts.validate(amount, unlock_ts, now)?;
+
+    ctx.accounts.transfer_in(amount)?;
+
+    let lock = &mut ctx.accounts.lock;
+    lock.config = ctx.accounts.config.key();
+    lock.owner = ctx.accounts.owner.key();
+    lock.amount = amount;
+    lock.released = 0;
+    lock.start_ts = now;
+    lock.unlock_ts = unlock_ts;
+    lock.linear = linear;
+    lock.bump = ctx.bumps.lock;
+
+    emit!(LpLocked {
+        config: lock.config,
+        owner: lock.owner,
+        amount,
+        unlock_ts,
+        linear,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::{
+    constants::*,
+    state::{CircuitBreaker, Config},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ManageCircuitBreaker<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+}
+
+impl<'info> ManageCircuitBreaker<'info> {
+    /// Validate the caller is the pool authority
+    pub fn validate(&self) -> Result<()> {
+        self.config.load()?.can_modify(&self.authority.key())
+    }
+}
+
+/// Handler function for configuring the circuit breaker
+pub fn set_circuit_breaker_handler(
+    ctx: Context<ManageCircuitBreaker>,
+    threshold: u32,
+    window: i64,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+    CircuitBreaker::validate(threshold, window)?;
+
+    // settings change restarts the window
+    ctx.accounts.config.load_mut()?.circuit_breaker = CircuitBreaker {
+        threshold,
+        window,
+        ..CircuitBreaker::default()
+    };
+
+    Ok(())
+}
+
+/// Handler function for resetting a tripped circuit breaker and resuming swaps
+pub fn reset_circuit_breaker_handler(ctx: Context<ManageCircuitBreaker>) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate()?;
+
+    // only lift the pause the breaker set, a manual swap pause stays
+    let mut config = ctx.accounts.config.load_mut()?;
+    if config.circuit_breaker.is_tripped() {
+        config.paused &= !PAUSE_SWAP;
+    }
+    config.circuit_breaker.reset();
+
+    Ok(())
+}
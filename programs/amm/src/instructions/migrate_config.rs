@@ -0,0 +1,87 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{Config, ConfigV0},
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// Pays for the extra rent of the larger layout
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Config in the legacy layout, only its discriminator and owner are checked here
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    /// Read the legacy layout and make sure the account is a pool config PDA
+    pub fn load_legacy(&self) -> Result<ConfigV0> {
+        let info = self.config.to_account_info();
+        let data = info.try_borrow_data()?;
+
+        // the legacy layout has no version byte, so it is told apart by size
+        require!(
+            data.len() == 8 + ConfigV0::INIT_SPACE,
+            AmmError::InvalidConfigLayout
+        );
+        // `authority` is an Option, so a pool without one leaves trailing zeroes
+        let legacy = ConfigV0::deserialize(&mut &data[8..])?;
+
+        let expected = Pubkey::create_program_address(
+            &[
+                CONFIG_SEED.as_bytes(),
+                legacy.seed.to_le_bytes().as_ref(),
+                &[legacy.config_bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(expected, self.config.key(), ErrorCode::ConstraintSeeds);
+
+        Ok(legacy)
+    }
+
+    /// Resize the account to the current layout, topping up rent from the payer
+    pub fn realloc(&self, new_len: usize) -> Result<()> {
+        let info = self.config.to_account_info();
+        let rent = Rent::get()?.minimum_balance(new_len);
+        let shortfall = rent.saturating_sub(info.lamports());
+
+        if shortfall > 0 {
+            let transfer_ctx = CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.payer.to_account_info(),
+                    to: info.clone(),
+                },
+            );
+            system_program::transfer(transfer_ctx, shortfall)?;
+        }
+
+        info.realloc(new_len, true)?;
+
+        Ok(())
+    }
+}
+
+/// Handler function for migrating a legacy config to the current layout
+pub fn migrate_config_handler(ctx: Context<MigrateConfig>) -> Result<()> {
+    let slot = Clock::get()?.slot;
+    let migrated = ctx.accounts.load_legacy()?.migrate(slot);
+
+    ctx.accounts.realloc(8 + Config::LEN)?;
+
+    // the discriminator is unchanged, everything after it is rewritten
+    *ctx.accounts.config.load_mut()? = migrated;
+
+    Ok(())
+}
@@ -1,9 +1,61 @@
+pub mod batch_swap;
+pub mod claim_rewards;
+pub mod close_pool;
+pub mod create_lp_metadata;
 pub mod deposit;
+pub mod emergency_withdraw;
+pub mod fee_tiers;
+pub mod flash_loan;
+pub mod flash_swap;
+pub mod fund_farm;
 pub mod initialize;
+pub mod initialize_factory;
+pub mod initialize_farm;
+pub mod lock_lp;
+pub mod manage_circuit_breaker;
+pub mod migrate_config;
+pub mod route_swap;
+pub mod set_dynamic_fee;
+pub mod set_pause;
+pub mod set_reward_rate;
+pub mod set_swap_limits;
+pub mod stake_lp;
 pub mod swap;
+pub mod swap_v2;
+pub mod swap_v3;
+pub mod unlock_lp;
+pub mod unstake_lp;
+pub mod update_factory;
 pub mod withdraw;
+pub mod withdraw_single;
 
+pub use batch_swap::*;
+pub use claim_rewards::*;
+pub use close_pool::*;
+pub use create_lp_metadata::*;
 pub use deposit::*;
+pub use emergency_withdraw::*;
+pub use fee_tiers::*;
+pub use flash_loan::*;
+pub use flash_swap::*;
+pub use fund_farm::*;
 pub use initialize::*;
+pub use initialize_factory::*;
+pub use initialize_farm::*;
+pub use lock_lp::*;
+pub use manage_circuit_breaker::*;
+pub use migrate_config::*;
+pub use route_swap::*;
+pub use set_dynamic_fee::*;
+pub use set_pause::*;
+pub use set_reward_rate::*;
+pub use set_swap_limits::*;
+pub use stake_lp::*;
 pub use swap::*;
+pub use swap_v2::*;
+pub use swap_v3::*;
+pub use unlock_lp::*;
+pub use unstake_lp::*;
+pub use update_factory::*;
 pub use withdraw::*;
+pub use withdraw_single::*;
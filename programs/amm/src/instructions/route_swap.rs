@@ -0,0 +1,281 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+/// Pool accounts passed through `remaining_accounts`: `[config, vault_x, vault_y]`
+pub struct PoolAccounts<'info> {
+    /// AMM config account
+    pub config: AccountLoader<'info, Config>,
+
+    /// Vault for token X
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    pub vault_y: Account<'info, TokenAccount>,
+}
+
+impl<'info> PoolAccounts<'info> {
+    /// Number of remaining accounts per pool
+    pub const LEN: usize = 3;
+
+    /// Deserialize and validate a pool from remaining accounts
+    pub fn load(accounts: &'info [AccountInfo<'info>]) -> Result<Self> {
+        let [config_info, vault_x_info, vault_y_info] = accounts else {
+            return err!(AmmError::InvalidRoute);
+        };
+
+        // config is updated and both vaults move tokens
+        require!(
+            config_info.is_writable && vault_x_info.is_writable && vault_y_info.is_writable,
+            AmmError::InvalidRoute
+        );
+
+        // owner and discriminator checks: only pools created by this program pass
+        let config: AccountLoader<'info, Config> = AccountLoader::try_from(config_info)?;
+        let seed = {
+            let pool = config.load()?;
+            require!(!pool.is_paused(PAUSE_SWAP), AmmError::SwapsPaused);
+            require!(!pool.is_flash_loan_active(), AmmError::FlashLoanActive);
+            pool.seed.to_le_bytes()
+        };
+
+        // vaults must be the pool's PDAs
+        let (vault_x_key, _) =
+            Pubkey::find_program_address(&[VAULT_X_SEED.as_bytes(), seed.as_ref()], &crate::ID);
+        let (vault_y_key, _) =
+            Pubkey::find_program_address(&[VAULT_Y_SEED.as_bytes(), seed.as_ref()], &crate::ID);
+        require_keys_eq!(vault_x_info.key(), vault_x_key, AmmError::InvalidVault);
+        require_keys_eq!(vault_y_info.key(), vault_y_key, AmmError::InvalidVault);
+
+        Ok(Self {
+            config,
+            vault_x: Account::try_from(vault_x_info)?,
+            vault_y: Account::try_from(vault_y_info)?,
+        })
+    }
+
+    /// Swap direction for an input mint (true = X to Y)
+    pub fn direction(&self, mint_in: &Pubkey) -> Result<bool> {
+        let config = self.config.load()?;
+        if *mint_in == config.mint_x {
+            Ok(true)
+        } else if *mint_in == config.mint_y {
+            Ok(false)
+        } else {
+            err!(AmmError::InvalidToken)
+        }
+    }
+
+    /// Output mint for a direction
+    pub fn mint_out(&self, is_x_to_y: bool) -> Result<Pubkey> {
+        let config = self.config.load()?;
+        Ok(if is_x_to_y {
+            config.mint_y
+        } else {
+            config.mint_x
+        })
+    }
+
+    /// Vault receiving the input token
+    pub fn vault_in(&self, is_x_to_y: bool) -> AccountInfo<'info> {
+        if is_x_to_y {
+            self.vault_x.to_account_info()
+        } else {
+            self.vault_y.to_account_info()
+        }
+    }
+
+    /// Transfer the output token from the pool vault, signed by the pool config
+    pub fn transfer_out(
+        &self,
+        is_x_to_y: bool,
+        amount_out: u64,
+        to: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        let (seed, bump) = {
+            let config = self.config.load()?;
+            (config.seed.to_le_bytes(), config.config_bump)
+        };
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let from = if is_x_to_y {
+            self.vault_y.to_account_info()
+        } else {
+            self.vault_x.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            token_program,
+            Transfer {
+                from,
+                to,
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount_out)
+    }
+}
+
+/// A quoted hop of a route
+struct RouteHop<'info> {
+    pool: PoolAccounts<'info>,
+    is_x_to_y: bool,
+    amount_in: u64,
+    amount_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct RouteSwap<'info> {
+    /// User performing the swap
+    pub user: Signer<'info>,
+
+    /// User's token account for the route input
+    #[account(
+        mut,
+        token::authority = user,
+    )]
+    pub user_in: Account<'info, TokenAccount>,
+
+    /// User's token account for the route output
+    #[account(
+        mut,
+        token::authority = user,
+    )]
+    pub user_out: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> RouteSwap<'info> {
+    /// Validate route swap parameters
+    pub fn validate(
+        &self,
+        amount_in: u64,
+        min_out: u64,
+        hops: u8,
+        remaining_accounts: usize,
+    ) -> Result<()> {
+        // check positive amounts
+        require!(amount_in > 0 && min_out > 0, AmmError::InvalidAmount);
+
+        // one set of pool accounts per hop
+        require!(hops > 0 && hops <= MAX_ROUTE_HOPS, AmmError::InvalidRoute);
+        require!(
+            remaining_accounts == hops as usize * PoolAccounts::LEN,
+            AmmError::InvalidRoute
+        );
+
+        // check user has sufficient balance
+        require!(
+            self.user_in.amount >= amount_in,
+            AmmError::InsufficientBalance
+        );
+
+        Ok(())
+    }
+
+    /// Transfer tokens from user to the first pool's vault (token in)
+    pub fn transfer_in(&self, vault_in: AccountInfo<'info>, amount_in: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_in.to_account_info(),
+                to: vault_in,
+                authority: self.user.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount_in)
+    }
+}
+
+pub fn route_swap_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+    amount_in: u64,
+    min_out: u64,
+    hops: u8,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts
+        .validate(amount_in, min_out, hops, ctx.remaining_accounts.len())?;
+
+    // quote every hop against the current reserves
+    let mut route: Vec<RouteHop> = Vec::with_capacity(hops as usize);
+    let mut mint_in = ctx.accounts.user_in.mint;
+    let mut amount = amount_in;
+
+    for pool_accounts in ctx.remaining_accounts.chunks(PoolAccounts::LEN) {
+        let pool = PoolAccounts::load(pool_accounts)?;
+
+        // a pool can only appear once, its reserves are quoted up front
+        require!(
+            route
+                .iter()
+                .all(|hop| hop.pool.config.key() != pool.config.key()),
+            AmmError::InvalidRoute
+        );
+
+        let is_x_to_y = pool.direction(&mint_in)?;
+        let (reserve_in, reserve_out) =
+            Config::reserves_in_out(is_x_to_y, pool.vault_x.amount, pool.vault_y.amount);
+        require!(reserve_in > 0 && reserve_out > 0, AmmError::ZeroBalance);
+
+        let config = pool.config.load()?;
+        let amount_out = config.calculate_amount_out(amount, reserve_in, reserve_out)?;
+        config.check_swap_limits(amount, amount_out, reserve_in, reserve_out)?;
+        drop(config);
+
+        mint_in = pool.mint_out(is_x_to_y)?;
+        route.push(RouteHop {
+            pool,
+            is_x_to_y,
+            amount_in: amount,
+            amount_out,
+        });
+        amount = amount_out;
+    }
+
+    // output account must hold the final token
+    require_keys_eq!(ctx.accounts.user_out.mint, mint_in, AmmError::InvalidToken);
+
+    // slippage protection on the final amount only
+    require!(amount >= min_out, AmmError::SlippageExceeded);
+
+    // execute transfers
+    // 1. user -> first pool (token in)
+    let first = &route[0];
+    ctx.accounts
+        .transfer_in(first.pool.vault_in(first.is_x_to_y), amount_in)?;
+
+    // 2. each pool pays the next pool's vault directly, the last pays the user
+    for (i, hop) in route.iter().enumerate() {
+        let to = match route.get(i + 1) {
+            Some(next) => next.pool.vault_in(next.is_x_to_y),
+            None => ctx.accounts.user_out.to_account_info(),
+        };
+        hop.pool.transfer_out(
+            hop.is_x_to_y,
+            hop.amount_out,
+            to,
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    // 3. track the new prices for the dynamic fee and the circuit breaker
+    for hop in route.iter() {
+        let before = (hop.pool.vault_x.amount, hop.pool.vault_y.amount);
+        let after = Config::post_swap_reserves(
+            hop.is_x_to_y,
+            before.0,
+            before.1,
+            hop.amount_in,
+            hop.amount_out,
+        )?;
+        hop.pool.config.load_mut()?.record_swap(before, after)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{Config, DynamicFee, Factory},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDynamicFee<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Global factory holding the allowed fee tiers
+    #[account(
+        seeds = [FACTORY_SEED.as_bytes()],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+}
+
+impl<'info> SetDynamicFee<'info> {
+    /// Validate dynamic fee parameters
+    pub fn validate(&self, min_fee: u16, max_fee: u16, decay_period: i64) -> Result<()> {
+        // only the pool authority can change fee settings
+        self.config.load()?.can_modify(&self.authority.key())?;
+
+        DynamicFee::validate(min_fee, max_fee, decay_period)?;
+
+        // the fee moves between two governance-approved tiers
+        require!(
+            self.factory.is_fee_tier_allowed(min_fee) && self.factory.is_fee_tier_allowed(max_fee),
+            AmmError::FeeTierNotAllowed
+        );
+
+        Ok(())
+    }
+}
+
+/// Handler function for configuring the dynamic fee
+pub fn set_dynamic_fee_handler(
+    ctx: Context<SetDynamicFee>,
+    enabled: bool,
+    min_fee: u16,
+    max_fee: u16,
+    volatility_factor: u16,
+    decay_period: i64,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(min_fee, max_fee, decay_period)?;
+
+    // settings change resets the volatility state
+    ctx.accounts.config.load_mut()?.dynamic_fee = DynamicFee {
+        enabled: enabled as u8,
+        min_fee,
+        max_fee,
+        volatility_factor,
+        decay_period,
+        ..DynamicFee::default()
+    };
+
+    Ok(())
+}
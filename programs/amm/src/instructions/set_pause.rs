@@ -0,0 +1,39 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+}
+
+impl<'info> SetPause<'info> {
+    /// Validate pause parameters
+    pub fn validate(&self, paused: u8) -> Result<()> {
+        // only the pool authority can pause operations
+        self.config.load()?.can_modify(&self.authority.key())?;
+
+        require!(paused & !PAUSE_ALL == 0, AmmError::InvalidPauseFlags);
+
+        Ok(())
+    }
+}
+
+/// Handler function for setting the paused operations
+pub fn set_pause_handler(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(paused)?;
+
+    let slot = Clock::get()?.slot;
+    ctx.accounts.config.load_mut()?.set_paused(paused, slot);
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::{constants::*, error::AmmError, state::Farm};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetRewardRate<'info> {
+    /// Reward authority of the farm
+    pub reward_authority: Signer<'info>,
+
+    /// Farm to update
+    #[account(
+        mut,
+        seeds = [FARM_SEED.as_bytes(), farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+        has_one = reward_authority @ AmmError::Unauthorized,
+    )]
+    pub farm: Account<'info, Farm>,
+}
+
+/// Handler function for changing a farm's emission rate
+pub fn set_reward_rate_handler(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+    // settle emissions at the old rate before switching
+    let farm = &mut ctx.accounts.farm;
+    farm.update(Clock::get()?.unix_timestamp)?;
+    farm.reward_rate = reward_rate;
+
+    Ok(())
+}
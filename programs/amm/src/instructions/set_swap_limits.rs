@@ -0,0 +1,44 @@
+use crate::{constants::*, state::Config};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSwapLimits<'info> {
+    /// Pool authority
+    pub authority: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+    )]
+    pub config: AccountLoader<'info, Config>,
+}
+
+impl<'info> SetSwapLimits<'info> {
+    /// Validate swap limit parameters
+    pub fn validate(&self, max_price_impact: u16, max_trade_size: u16) -> Result<()> {
+        // only the pool authority can change swap limits
+        self.config.load()?.can_modify(&self.authority.key())?;
+
+        Config::validate_swap_limits(max_price_impact, max_trade_size)?;
+
+        Ok(())
+    }
+}
+
+/// Handler function for setting the per-swap price impact and trade size limits
+pub fn set_swap_limits_handler(
+    ctx: Context<SetSwapLimits>,
+    max_price_impact: u16,
+    max_trade_size: u16,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(max_price_impact, max_trade_size)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.max_price_impact = max_price_impact;
+    config.max_trade_size = max_trade_size;
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{Farm, StakePosition},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct StakeLp<'info> {
+    /// User staking LP tokens
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Farm to stake in
+    #[account(
+        mut,
+        seeds = [FARM_SEED.as_bytes(), farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// User's stake position, created on the first stake
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [STAKE_POSITION_SEED.as_bytes(), farm.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// User's LP token account
+    #[account(
+        mut,
+        token::mint = farm.lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    /// Vault holding the staked LP tokens
+    #[account(
+        mut,
+        seeds = [FARM_LP_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump,
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeLp<'info> {
+    /// Validate stake parameters
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        // check amount is positive
+        require!(amount > 0, AmmError::InvalidAmount);
+
+        // check user has sufficient LP tokens
+        require!(self.user_lp.amount >= amount, AmmError::InsufficientBalance);
+
+        Ok(())
+    }
+
+    /// Transfer LP tokens from the user to the farm
+    pub fn transfer_in(&self, amount: u64) -> Result<()> {
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_lp.to_account_info(),
+                to: self.lp_vault.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+/// Handler function for staking LP tokens in a farm
+pub fn stake_lp_handler(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(amount)?;
+
+    ctx.accounts.transfer_in(amount)?;
+
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+
+    // a fresh position has nothing to settle
+    if position.farm == Pubkey::default() {
+        position.farm = farm.key();
+        position.owner = ctx.accounts.user.key();
+        position.bump = ctx.bumps.position;
+    }
+
+    // settle what the old stake earned before it grows
+    farm.update(Clock::get()?.unix_timestamp)?;
+    position.settle(farm.acc_reward_per_share)?;
+
+    let staked = position
+        .amount
+        .checked_add(amount)
+        .ok_or(AmmError::Overflow)?;
+    position.set_amount(staked, farm.acc_reward_per_share)?;
+    farm.total_staked = farm
+        .total_staked
+        .checked_add(amount)
+        .ok_or(AmmError::Overflow)?;
+
+    Ok(())
+}
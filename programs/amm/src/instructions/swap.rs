@@ -10,21 +10,23 @@ pub struct Swap<'info> {
 
     /// AMM config account
     #[account(
-        seeds = [CONFIG_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
-        bump = config.config_bump,
-        constraint = !config.locked @ AmmError::PoolLocked,
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_SWAP) @ AmmError::SwapsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
     )]
-    pub config: Account<'info, Config>,
+    pub config: AccountLoader<'info, Config>,
 
     /// First token mint
     #[account(
-        constraint = mint_x.key() == config.mint_x @ AmmError::InvalidToken
+        constraint = mint_x.key() == config.load()?.mint_x @ AmmError::InvalidToken
     )]
     pub mint_x: Account<'info, Mint>,
 
     /// Second token mint
     #[account(
-        constraint = mint_y.key() == config.mint_y @ AmmError::InvalidToken
+        constraint = mint_y.key() == config.load()?.mint_y @ AmmError::InvalidToken
     )]
     pub mint_y: Account<'info, Mint>,
 
@@ -47,7 +49,7 @@ pub struct Swap<'info> {
     /// Vault for token X
     #[account(
         mut,
-        seeds = [VAULT_X_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
         token::mint = mint_x,
         token::authority = config
@@ -57,7 +59,7 @@ pub struct Swap<'info> {
     /// Vault for token Y
     #[account(
         mut,
-        seeds = [VAULT_Y_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
         token::mint = mint_y,
         token::authority = config
@@ -66,14 +68,29 @@ pub struct Swap<'info> {
 
     /// SPL tokenprogram
     pub token_program: Program<'info, Token>,
+
+    /// Optional token account receiving the output instead of the user's account,
+    /// appended last so existing clients can leave it out
+    #[account(mut)]
+    pub recipient: Option<Account<'info, TokenAccount>>,
 }
 
 impl<'info> Swap<'info> {
     /// Validate swap parameters
-    pub fn validate(&self, amount_in: u64, min_out: u64) -> Result<()> {
+    pub fn validate(&self, is_x_to_y: bool, amount_in: u64, min_out: u64) -> Result<()> {
         // check positive amounts
         require!(amount_in > 0 && min_out > 0, AmmError::InvalidAmount);
 
+        // recipient must hold the output token
+        if let Some(recipient) = &self.recipient {
+            let mint_out = if is_x_to_y {
+                self.mint_y.key()
+            } else {
+                self.mint_x.key()
+            };
+            require_keys_eq!(recipient.mint, mint_out, AmmError::InvalidToken);
+        }
+
         // pool must have liquidity
         require!(
             self.vault_x.amount > 0 && self.vault_y.amount > 0,
@@ -83,15 +100,6 @@ impl<'info> Swap<'info> {
         Ok(())
     }
 
-    /// Read reserves based on direction
-    pub fn get_reserves(&self, is_x_to_y: bool) -> (u64, u64) {
-        if is_x_to_y {
-            (self.vault_x.amount, self.vault_y.amount)
-        } else {
-            (self.vault_y.amount, self.vault_x.amount)
-        }
-    }
-
     /// Constant product with fee: returns amount_out
     pub fn calculate_amount_out(
         &self,
@@ -99,36 +107,9 @@ impl<'info> Swap<'info> {
         reserve_in: u64,
         reserve_out: u64,
     ) -> Result<u64> {
-        // amount_in_with_fee = amount_in * (FEE_BASIS_POINTS - fee)
-        let fee_bps = self.config.fee as u128;
-        let denom_bps = FEE_BASIS_POINTS as u128;
-
-        let amount_in_u128 = amount_in as u128;
-        let reserve_in_u128 = reserve_in as u128;
-        let reserve_out_u128 = reserve_out as u128;
-
-        let amount_in_with_fee = amount_in_u128
-            .checked_mul(denom_bps.checked_sub(fee_bps).ok_or(AmmError::Underflow)?)
-            .ok_or(AmmError::Overflow)?;
-
-        // numerator = amount_in_with_fee * reserve_out
-        let numerator = amount_in_with_fee
-            .checked_mul(reserve_out_u128)
-            .ok_or(AmmError::Overflow)?;
-
-        // denominator = reserve_in * denom_bps + amount_int_with_fee
-        let denominator = reserve_in_u128
-            .checked_mul(denom_bps)
-            .ok_or(AmmError::Overflow)?
-            .checked_add(amount_in_with_fee)
-            .ok_or(AmmError::Overflow)?;
-
-        let amount_out = numerator
-            .checked_div(denominator)
-            .ok_or(AmmError::ZeroBalance)? as u64;
-
-        require!(amount_out > 0, AmmError::SlippageExceeded);
-        Ok(amount_out)
+        self.config
+            .load()?
+            .calculate_amount_out(amount_in, reserve_in, reserve_out)
     }
 
     /// Transfer tokens from user to vault (token in)
@@ -156,26 +137,22 @@ impl<'info> Swap<'info> {
         token::transfer(cpi_ctx, amount_in)
     }
 
-    /// Transfer tokens from vault to user (token out)
+    /// Transfer tokens from vault to user, or to the recipient when provided (token out)
     pub fn transfer_out(&self, is_x_to_y: bool, amount_out: u64, config_bump: u8) -> Result<()> {
-        let seeds = &[
-            CONFIG_SEED.as_bytes(),
-            &self.config.seed.to_le_bytes(),
-            &[config_bump],
-        ];
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
         let signer_seeds = &[&seeds[..]];
 
-        let (from, to) = if is_x_to_y {
-            (
-                self.vault_y.to_account_info(),
-                self.user_y.to_account_info(),
-            )
+        let (from, user_out) = if is_x_to_y {
+            (self.vault_y.to_account_info(), &self.user_y)
         } else {
-            (
-                self.vault_x.to_account_info(),
-                self.user_x.to_account_info(),
-            )
+            (self.vault_x.to_account_info(), &self.user_x)
         };
+        let to = self
+            .recipient
+            .as_ref()
+            .unwrap_or(user_out)
+            .to_account_info();
 
         let cpi_ctx = CpiContext::new_with_signer(
             self.token_program.to_account_info(),
@@ -197,20 +174,30 @@ pub fn swap_handler(
     min_out: u64,
 ) -> Result<()> {
     // validate inputs
-    ctx.accounts.validate(amount_in, min_out)?;
+    ctx.accounts.validate(is_x_to_y, amount_in, min_out)?;
 
-    let config_bump = ctx.accounts.config.config_bump;
+    let config_bump = ctx.accounts.config.load()?.config_bump;
 
     // read reserves based on direction
-    let (reserve_in, reserve_out) = ctx.accounts.get_reserves(is_x_to_y);
+    let (reserve_in, reserve_out) = Config::reserves_in_out(
+        is_x_to_y,
+        ctx.accounts.vault_x.amount,
+        ctx.accounts.vault_y.amount,
+    );
 
     // calculate output amount
     let amount_out = ctx
         .accounts
         .calculate_amount_out(amount_in, reserve_in, reserve_out)?;
 
-    // slippage protection
+    // slippage protection and pool-level limits
     require!(amount_out >= min_out, AmmError::SlippageExceeded);
+    ctx.accounts.config.load()?.check_swap_limits(
+        amount_in,
+        amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
 
     // execute transfer
     // 1. user -> vault (token in)
@@ -220,5 +207,10 @@ pub fn swap_handler(
     ctx.accounts
         .transfer_out(is_x_to_y, amount_out, config_bump)?;
 
+    // track the new price for the dynamic fee and the circuit breaker
+    let before = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    let after = Config::post_swap_reserves(is_x_to_y, before.0, before.1, amount_in, amount_out)?;
+    ctx.accounts.config.load_mut()?.record_swap(before, after)?;
+
     Ok(())
 }
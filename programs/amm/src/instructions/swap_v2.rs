@@ -0,0 +1,168 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct SwapV2<'info> {
+    /// User performing the swap
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_SWAP) @ AmmError::SwapsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// User's token account for the input token, its mint picks the direction
+    #[account(
+        mut,
+        token::authority = user,
+    )]
+    pub user_in: Account<'info, TokenAccount>,
+
+    /// Token account receiving the output, owned by the user or a recipient
+    #[account(mut)]
+    pub user_out: Account<'info, TokenAccount>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_x,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_y,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> SwapV2<'info> {
+    /// Validate swap parameters and return the direction (true = X to Y)
+    pub fn validate(&self, amount_in: u64, min_out: u64) -> Result<bool> {
+        // check positive amounts
+        require!(amount_in > 0 && min_out > 0, AmmError::InvalidAmount);
+
+        // the token accounts stand in for the mint accounts of `swap`
+        let config = self.config.load()?;
+        let is_x_to_y = if self.user_in.mint == config.mint_x {
+            true
+        } else if self.user_in.mint == config.mint_y {
+            false
+        } else {
+            return err!(AmmError::InvalidToken);
+        };
+        let mint_out = if is_x_to_y {
+            config.mint_y
+        } else {
+            config.mint_x
+        };
+        require_keys_eq!(self.user_out.mint, mint_out, AmmError::InvalidToken);
+
+        // pool must have liquidity
+        require!(
+            self.vault_x.amount > 0 && self.vault_y.amount > 0,
+            AmmError::ZeroBalance
+        );
+
+        // check user has sufficient balance
+        require!(
+            self.user_in.amount >= amount_in,
+            AmmError::InsufficientBalance
+        );
+
+        Ok(is_x_to_y)
+    }
+
+    /// Transfer tokens from user to vault (token in)
+    pub fn transfer_in(&self, is_x_to_y: bool, amount_in: u64) -> Result<()> {
+        let to = if is_x_to_y {
+            self.vault_x.to_account_info()
+        } else {
+            self.vault_y.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_in.to_account_info(),
+                to,
+                authority: self.user.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount_in)
+    }
+
+    /// Transfer tokens from vault to the output account (token out)
+    pub fn transfer_out(&self, is_x_to_y: bool, amount_out: u64) -> Result<()> {
+        let (seed, bump) = {
+            let config = self.config.load()?;
+            (config.seed.to_le_bytes(), config.config_bump)
+        };
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let from = if is_x_to_y {
+            self.vault_y.to_account_info()
+        } else {
+            self.vault_x.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from,
+                to: self.user_out.to_account_info(),
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount_out)
+    }
+}
+
+/// Handler function for swapping without the mint accounts, native SOL must be wrapped by the caller
+pub fn swap_v2_handler(ctx: Context<SwapV2>, amount_in: u64, min_out: u64) -> Result<()> {
+    // validate inputs
+    let is_x_to_y = ctx.accounts.validate(amount_in, min_out)?;
+
+    // read reserves based on direction
+    let (reserve_in, reserve_out) = Config::reserves_in_out(
+        is_x_to_y,
+        ctx.accounts.vault_x.amount,
+        ctx.accounts.vault_y.amount,
+    );
+
+    // calculate output amount, slippage protection and pool-level limits
+    let amount_out = {
+        let config = ctx.accounts.config.load()?;
+        let amount_out = config.calculate_amount_out(amount_in, reserve_in, reserve_out)?;
+        require!(amount_out >= min_out, AmmError::SlippageExceeded);
+        config.check_swap_limits(amount_in, amount_out, reserve_in, reserve_out)?;
+        amount_out
+    };
+
+    // execute transfers
+    ctx.accounts.transfer_in(is_x_to_y, amount_in)?;
+    ctx.accounts.transfer_out(is_x_to_y, amount_out)?;
+
+    // track the new price for the dynamic fee and the circuit breaker
+    let before = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    let after = Config::post_swap_reserves(is_x_to_y, before.0, before.1, amount_in, amount_out)?;
+    ctx.accounts.config.load_mut()?.record_swap(before, after)?;
+
+    Ok(())
+}
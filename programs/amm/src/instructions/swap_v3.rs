@@ -0,0 +1,317 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    native_sol::{close_temp_wsol, is_native, open_temp_wsol},
+    state::Config,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Mint, Token, TokenAccount, Transfer},
+};
+
+#[derive(Accounts)]
+pub struct SwapV3<'info> {
+    /// User performing the swap
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_SWAP) @ AmmError::SwapsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Mint of the token paid in
+    #[account(
+        constraint = mint_in.key() == config.load()?.mint_x
+            || mint_in.key() == config.load()?.mint_y @ AmmError::InvalidToken
+    )]
+    pub mint_in: Account<'info, Mint>,
+
+    /// Mint of the token received, the other side of the pool
+    #[account(
+        constraint = mint_out.key() != mint_in.key() @ AmmError::InvalidToken,
+        constraint = mint_out.key() == config.load()?.mint_x
+            || mint_out.key() == config.load()?.mint_y @ AmmError::InvalidToken
+    )]
+    pub mint_out: Account<'info, Mint>,
+
+    /// User's account for the input token, any token account the user owns,
+    /// omitted when paying native SOL through `wsol_temp`
+    #[account(
+        mut,
+        token::mint = mint_in,
+        token::authority = user,
+    )]
+    pub user_in: Option<Account<'info, TokenAccount>>,
+
+    /// User's output token ATA, created on first use, omitted when a recipient
+    /// or `wsol_temp` receives the output
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_out,
+        associated_token::authority = user,
+    )]
+    pub user_out: Option<Account<'info, TokenAccount>>,
+
+    /// Optional token account receiving the output instead of the user's account
+    #[account(
+        mut,
+        token::mint = mint_out,
+    )]
+    pub recipient: Option<Account<'info, TokenAccount>>,
+
+    /// Temporary wSOL account, pass it to pay or receive the native side as SOL
+    /// CHECK: created and closed within the instruction at this PDA
+    #[account(
+        mut,
+        seeds = [WSOL_TEMP_SEED.as_bytes(), user.key().as_ref()],
+        bump,
+    )]
+    pub wsol_temp: Option<UncheckedAccount<'info>>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_x,
+        token::authority = config
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = config.load()?.mint_y,
+        token::authority = config
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// SPL tokenprogram
+    pub token_program: Program<'info, Token>,
+
+    /// Associated token program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapV3<'info> {
+    /// Validate swap parameters and return the direction (true = X to Y)
+    pub fn validate(&self, amount_in: u64, min_out: u64) -> Result<bool> {
+        // check positive amounts
+        require!(amount_in > 0 && min_out > 0, AmmError::InvalidAmount);
+
+        // the input mint picks the direction
+        let is_x_to_y = self.mint_in.key() == self.config.load()?.mint_x;
+
+        // the temporary wSOL account stands in for the native side
+        if self.wsol_temp.is_some() {
+            require!(
+                is_native(&self.mint_in.key()) || is_native(&self.mint_out.key()),
+                AmmError::InvalidToken
+            );
+        }
+
+        // the output goes to exactly one account
+        let native_out = self.uses_temp_wsol(&self.mint_out.key());
+        let outputs = [
+            self.user_out.is_some(),
+            self.recipient.is_some(),
+            native_out,
+        ];
+        require!(
+            outputs.iter().filter(|output| **output).count() == 1,
+            AmmError::InvalidOutputAccount
+        );
+
+        // pool must have liquidity
+        require!(
+            self.vault_x.amount > 0 && self.vault_y.amount > 0,
+            AmmError::ZeroBalance
+        );
+
+        Ok(is_x_to_y)
+    }
+
+    /// Check the input account holds `amount_in`
+    pub fn validate_balance(&self, amount_in: u64) -> Result<()> {
+        // the temporary wSOL account is funded with exactly `amount_in`
+        if self.uses_temp_wsol(&self.mint_in.key()) {
+            return Ok(());
+        }
+
+        let user_in = self.user_in.as_ref().ok_or(AmmError::MissingTokenAccount)?;
+        require!(user_in.amount >= amount_in, AmmError::InsufficientBalance);
+
+        Ok(())
+    }
+
+    /// Whether `mint` is paid or received through the temporary wSOL account
+    fn uses_temp_wsol(&self, mint: &Pubkey) -> bool {
+        self.wsol_temp.is_some() && is_native(mint)
+    }
+
+    /// Constant product with fee: returns amount_out
+    pub fn calculate_amount_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<u64> {
+        self.config
+            .load()?
+            .calculate_amount_out(amount_in, reserve_in, reserve_out)
+    }
+
+    /// Create the temporary wSOL account for the native side, if given, wrapping
+    /// `amount_in` when SOL is paid in
+    pub fn wrap_native(&self, amount_in: u64, bump: Option<u8>) -> Result<()> {
+        let (Some(wsol_temp), Some(bump)) = (&self.wsol_temp, bump) else {
+            return Ok(());
+        };
+
+        let (mint, amount) = if is_native(&self.mint_in.key()) {
+            (&self.mint_in, amount_in)
+        } else {
+            (&self.mint_out, 0)
+        };
+
+        open_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            bump,
+            &mint.to_account_info(),
+            amount,
+            &self.system_program.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+
+    /// Close the temporary wSOL account, if given, so the user ends with native SOL
+    pub fn unwrap_native(&self) -> Result<()> {
+        let Some(wsol_temp) = &self.wsol_temp else {
+            return Ok(());
+        };
+
+        close_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+
+    /// Transfer tokens from user to vault (token in)
+    pub fn transfer_in(&self, is_x_to_y: bool, amount_in: u64) -> Result<()> {
+        let to = if is_x_to_y {
+            self.vault_x.to_account_info()
+        } else {
+            self.vault_y.to_account_info()
+        };
+
+        let from = match (&self.wsol_temp, &self.user_in) {
+            (Some(wsol_temp), _) if is_native(&self.mint_in.key()) => wsol_temp.to_account_info(),
+            (_, Some(user_in)) => user_in.to_account_info(),
+            _ => return err!(AmmError::MissingTokenAccount),
+        };
+
+        let cpi_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from,
+                to,
+                authority: self.user.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, amount_in)
+    }
+
+    /// Transfer tokens from vault to the recipient, the user, or the temporary wSOL account (token out)
+    pub fn transfer_out(&self, is_x_to_y: bool, amount_out: u64, config_bump: u8) -> Result<()> {
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let from = if is_x_to_y {
+            self.vault_y.to_account_info()
+        } else {
+            self.vault_x.to_account_info()
+        };
+        let to = match (&self.recipient, &self.user_out, &self.wsol_temp) {
+            (Some(recipient), _, _) => recipient.to_account_info(),
+            (_, Some(user_out), _) => user_out.to_account_info(),
+            (_, _, Some(wsol_temp)) => wsol_temp.to_account_info(),
+            _ => return err!(AmmError::InvalidOutputAccount),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from,
+                to,
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount_out)
+    }
+}
+
+/// Handler function for swapping into an output ATA created on demand, paying or receiving native SOL through `wsol_temp`
+pub fn swap_v3_handler(ctx: Context<SwapV3>, amount_in: u64, min_out: u64) -> Result<()> {
+    // validate inputs
+    let is_x_to_y = ctx.accounts.validate(amount_in, min_out)?;
+
+    let config_bump = ctx.accounts.config.load()?.config_bump;
+
+    // read reserves based on direction
+    let (reserve_in, reserve_out) = Config::reserves_in_out(
+        is_x_to_y,
+        ctx.accounts.vault_x.amount,
+        ctx.accounts.vault_y.amount,
+    );
+
+    // calculate output amount
+    let amount_out = ctx
+        .accounts
+        .calculate_amount_out(amount_in, reserve_in, reserve_out)?;
+
+    // slippage protection and pool-level limits
+    require!(amount_out >= min_out, AmmError::SlippageExceeded);
+    ctx.accounts.config.load()?.check_swap_limits(
+        amount_in,
+        amount_out,
+        reserve_in,
+        reserve_out,
+    )?;
+
+    // execute transfer
+    // 1. user -> vault (token in), wrapping native SOL first when opted in
+    ctx.accounts.validate_balance(amount_in)?;
+    ctx.accounts.wrap_native(amount_in, ctx.bumps.wsol_temp)?;
+    ctx.accounts.transfer_in(is_x_to_y, amount_in)?;
+
+    // 2. vault -> user (token out) using PDA signer
+    ctx.accounts
+        .transfer_out(is_x_to_y, amount_out, config_bump)?;
+
+    // track the new price for the dynamic fee and the circuit breaker
+    let before = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    let after = Config::post_swap_reserves(is_x_to_y, before.0, before.1, amount_in, amount_out)?;
+    ctx.accounts.config.load_mut()?.record_swap(before, after)?;
+
+    // 3. hand back native SOL from the temporary wSOL account, if any
+    ctx.accounts.unwrap_native()?;
+
+    Ok(())
+}
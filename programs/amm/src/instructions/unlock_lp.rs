@@ -0,0 +1,117 @@
+use crate::{constants::*, error::AmmError, state::LpLock};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    /// Owner of the lock, receives the reclaimed rent once fully released
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Lock to release from
+    #[account(
+        mut,
+        seeds = [LP_LOCK_SEED.as_bytes(), lock.config.as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ AmmError::Unauthorized,
+    )]
+    pub lock: Account<'info, LpLock>,
+
+    /// Vault escrowing the locked LP tokens
+    #[account(
+        mut,
+        seeds = [LP_LOCK_VAULT_SEED.as_bytes(), lock.key().as_ref()],
+        bump,
+    )]
+    pub lock_vault: Account<'info, TokenAccount>,
+
+    /// Owner's LP token account
+    #[account(
+        mut,
+        token::mint = lock_vault.mint,
+        token::authority = owner,
+    )]
+    pub owner_lp: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnlockLp<'info> {
+    /// Validate and return the LP tokens that can be released now
+    pub fn validate(&self, now: i64) -> Result<u64> {
+        let amount = self.lock.releasable(now);
+        require!(amount > 0, AmmError::NothingUnlocked);
+
+        Ok(amount)
+    }
+
+    /// Transfer released LP tokens to the owner, closing the vault once it is empty
+    pub fn release(&self, amount: u64, close_vault: bool) -> Result<()> {
+        let seeds = &[
+            LP_LOCK_SEED.as_bytes(),
+            self.lock.config.as_ref(),
+            self.lock.owner.as_ref(),
+            &[self.lock.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        // the last release also sweeps anything sent to the vault directly
+        let amount = if close_vault {
+            self.lock_vault.amount
+        } else {
+            amount
+        };
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lock_vault.to_account_info(),
+                to: self.owner_lp.to_account_info(),
+                authority: self.lock.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(transfer_ctx, amount)?;
+
+        if !close_vault {
+            return Ok(());
+        }
+
+        let close_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.lock_vault.to_account_info(),
+                destination: self.owner.to_account_info(),
+                authority: self.lock.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::close_account(close_ctx)
+    }
+}
+
+/// Handler function for releasing vested LP tokens from a lock
+pub fn unlock_lp_handler(ctx: Context<UnlockLp>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // validate inputs
+    let amount = ctx.accounts.validate(now)?;
+
+    let lock = &mut ctx.accounts.lock;
+    lock.released = lock
+        .released
+        .checked_add(amount)
+        .ok_or(AmmError::Overflow)?;
+    let fully_released = lock.released == lock.amount;
+
+    ctx.accounts.release(amount, fully_released)?;
+
+    // the lock is spent, return its rent so the owner can lock again
+    if fully_released {
+        let owner = ctx.accounts.owner.to_account_info();
+        ctx.accounts.lock.close(owner)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::{
+    constants::*,
+    error::AmmError,
+    state::{Farm, StakePosition},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct UnstakeLp<'info> {
+    /// User unstaking LP tokens
+    pub user: Signer<'info>,
+
+    /// Farm to unstake from
+    #[account(
+        mut,
+        seeds = [FARM_SEED.as_bytes(), farm.config.as_ref(), farm.reward_mint.as_ref()],
+        bump = farm.bump,
+    )]
+    pub farm: Account<'info, Farm>,
+
+    /// User's stake position
+    #[account(
+        mut,
+        seeds = [STAKE_POSITION_SEED.as_bytes(), farm.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, StakePosition>,
+
+    /// User's LP token account
+    #[account(
+        mut,
+        token::mint = farm.lp_mint,
+        token::authority = user,
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    /// Vault holding the staked LP tokens
+    #[account(
+        mut,
+        seeds = [FARM_LP_VAULT_SEED.as_bytes(), farm.key().as_ref()],
+        bump,
+    )]
+    pub lp_vault: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnstakeLp<'info> {
+    /// Validate unstake parameters
+    pub fn validate(&self, amount: u64) -> Result<()> {
+        // check amount is positive
+        require!(amount > 0, AmmError::InvalidAmount);
+
+        // check the position holds enough
+        require!(self.position.amount >= amount, AmmError::InsufficientStake);
+
+        Ok(())
+    }
+
+    /// Transfer LP tokens from the farm back to the user
+    pub fn transfer_out(&self, amount: u64) -> Result<()> {
+        let seeds = &[
+            FARM_SEED.as_bytes(),
+            self.farm.config.as_ref(),
+            self.farm.reward_mint.as_ref(),
+            &[self.farm.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.lp_vault.to_account_info(),
+                to: self.user_lp.to_account_info(),
+                authority: self.farm.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)
+    }
+}
+
+/// Handler function for unstaking LP tokens, earned rewards stay claimable
+pub fn unstake_lp_handler(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(amount)?;
+
+    // settle what the stake earned before it shrinks
+    let farm = &mut ctx.accounts.farm;
+    let position = &mut ctx.accounts.position;
+    farm.update(Clock::get()?.unix_timestamp)?;
+    position.settle(farm.acc_reward_per_share)?;
+
+    let staked = position.amount - amount;
+    position.set_amount(staked, farm.acc_reward_per_share)?;
+    farm.total_staked = farm
+        .total_staked
+        .checked_sub(amount)
+        .ok_or(AmmError::Underflow)?;
+
+    ctx.accounts.transfer_out(amount)
+}
@@ -0,0 +1,44 @@
+use crate::{constants::*, state::Factory};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateFactory<'info> {
+    /// Current global admin
+    pub admin: Signer<'info>,
+
+    /// The factory account
+    #[account(
+        mut,
+        seeds = [FACTORY_SEED.as_bytes()],
+        bump = factory.bump,
+    )]
+    pub factory: Account<'info, Factory>,
+}
+
+impl<'info> UpdateFactory<'info> {
+    /// Validate the update parameters
+    pub fn validate(&self, protocol_fee: u16) -> Result<()> {
+        // only the current admin can update the factory
+        self.factory.can_modify(&self.admin.key())?;
+
+        Factory::validate_protocol_fee(protocol_fee)?;
+
+        Ok(())
+    }
+}
+
+/// Handler function for updating global factory settings
+pub fn update_factory_handler(
+    ctx: Context<UpdateFactory>,
+    new_admin: Pubkey,
+    protocol_fee: u16,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(protocol_fee)?;
+
+    let factory = &mut ctx.accounts.factory;
+    factory.admin = new_admin;
+    factory.protocol_fee = protocol_fee;
+
+    Ok(())
+}
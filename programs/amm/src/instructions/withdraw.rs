@@ -1,4 +1,9 @@
-use crate::{constants::*, error::AmmError, state::Config};
+use crate::{
+    constants::*,
+    error::AmmError,
+    native_sol::{close_temp_wsol, is_native, open_temp_wsol},
+    state::Config,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
@@ -10,47 +15,36 @@ pub struct Withdraw<'info> {
 
     /// AMM config account
     #[account(
-        seeds = [CONFIG_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
-        bump = config.config_bump,
-        constraint = !config.locked @ AmmError::PoolLocked
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_WITHDRAW) @ AmmError::WithdrawalsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
     )]
-    pub config: Account<'info, Config>,
-
-    /// First token mint
-    #[account(
-        constraint = mint_x.key() == config.mint_x @ AmmError::InvalidToken
-    )]
-    pub mint_x: Account<'info, Mint>,
-
-    /// Second token mint
-    #[account(
-        constraint = mint_y.key() == config.mint_y @ AmmError::InvalidToken
-    )]
-    pub mint_y: Account<'info, Mint>,
+    pub config: AccountLoader<'info, Config>,
 
     /// LP token mint
     #[account(
         mut,
-        seeds = [LP_MINT_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
-        bump = config.lp_bump,
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
     )]
     pub lp_mint: Account<'info, Mint>,
 
-    /// User's token X account
+    /// User's token X account, may be omitted when X goes to a recipient or `wsol_temp`
     #[account(
         mut,
-        associated_token::mint = mint_x,
+        associated_token::mint = config.load()?.mint_x,
         associated_token::authority = user
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: Option<Account<'info, TokenAccount>>,
 
-    /// User's token Y account
+    /// User's token Y account, may be omitted when Y goes to a recipient or `wsol_temp`
     #[account(
         mut,
-        associated_token::mint = mint_y,
+        associated_token::mint = config.load()?.mint_y,
         associated_token::authority = user
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: Option<Account<'info, TokenAccount>>,
 
     /// User's LP token account
     #[account(
@@ -60,27 +54,57 @@ pub struct Withdraw<'info> {
     )]
     pub user_lp: Account<'info, TokenAccount>,
 
+    /// Optional token X account receiving the withdrawal instead of `user_x`
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_x,
+    )]
+    pub recipient_x: Option<Account<'info, TokenAccount>>,
+
+    /// Optional token Y account receiving the withdrawal instead of `user_y`
+    #[account(
+        mut,
+        token::mint = config.load()?.mint_y,
+    )]
+    pub recipient_y: Option<Account<'info, TokenAccount>>,
+
+    /// Temporary wSOL account, pass it to receive the native side as SOL instead of wSOL
+    /// CHECK: created and closed within the instruction at this PDA
+    #[account(
+        mut,
+        seeds = [WSOL_TEMP_SEED.as_bytes(), user.key().as_ref()],
+        bump,
+    )]
+    pub wsol_temp: Option<UncheckedAccount<'info>>,
+
+    /// Native mint, required with `wsol_temp`
+    #[account(constraint = is_native(&native_mint.key()) @ AmmError::InvalidToken)]
+    pub native_mint: Option<Account<'info, Mint>>,
+
     /// Vault for token X
     #[account(
         mut,
-        seeds = [VAULT_X_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
-        token::mint = mint_x,
+        token::mint = config.load()?.mint_x,
         token::authority = config,
     )]
     pub vault_x: Account<'info, TokenAccount>,
 
     #[account(
         mut,
-        seeds = [VAULT_Y_SEED.as_bytes(), config.seed.to_le_bytes().as_ref()],
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
         bump,
-        token::mint = mint_y,
+        token::mint = config.load()?.mint_y,
         token::authority = config,
     )]
     pub vault_y: Account<'info, TokenAccount>,
 
     /// SPL token program
     pub token_program: Program<'info, Token>,
+
+    /// System program
+    pub system_program: Program<'info, System>,
 }
 
 impl<'info> Withdraw<'info> {
@@ -132,18 +156,36 @@ impl<'info> Withdraw<'info> {
         Ok((amount_x, amount_y))
     }
 
-    /// Transfer tokens from vaults to user
+    /// Account receiving `mint`: the recipient, else the temporary wSOL account on the
+    /// native side when given, else the user's account
+    fn destination(
+        &self,
+        mint: &Pubkey,
+        recipient: &Option<Account<'info, TokenAccount>>,
+        user_account: &Option<Account<'info, TokenAccount>>,
+    ) -> Result<AccountInfo<'info>> {
+        if let Some(recipient) = recipient {
+            return Ok(recipient.to_account_info());
+        }
+        match (&self.wsol_temp, user_account) {
+            (Some(wsol_temp), _) if is_native(mint) => Ok(wsol_temp.to_account_info()),
+            (_, Some(user_account)) => Ok(user_account.to_account_info()),
+            _ => err!(AmmError::MissingTokenAccount),
+        }
+    }
+
+    /// Transfer tokens from vaults to user, or to the recipients when provided
     pub fn transfer_from_vaults(
         &self,
         amount_x: u64,
         amount_y: u64,
         config_bump: u8,
     ) -> Result<()> {
-        let seeds = &[
-            CONFIG_SEED.as_bytes(),
-            &self.config.seed.to_le_bytes(),
-            &[config_bump],
-        ];
+        let (seed, mint_x, mint_y) = {
+            let config = self.config.load()?;
+            (config.seed.to_le_bytes(), config.mint_x, config.mint_y)
+        };
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
         let signer_seeds = &[&seeds[..]];
 
         // transfer token X from vault
@@ -151,7 +193,7 @@ impl<'info> Withdraw<'info> {
             self.token_program.to_account_info(),
             Transfer {
                 from: self.vault_x.to_account_info(),
-                to: self.user_x.to_account_info(),
+                to: self.destination(&mint_x, &self.recipient_x, &self.user_x)?,
                 authority: self.config.to_account_info(),
             },
             signer_seeds,
@@ -163,7 +205,7 @@ impl<'info> Withdraw<'info> {
             self.token_program.to_account_info(),
             Transfer {
                 from: self.vault_y.to_account_info(),
-                to: self.user_y.to_account_info(),
+                to: self.destination(&mint_y, &self.recipient_y, &self.user_y)?,
                 authority: self.config.to_account_info(),
             },
             signer_seeds,
@@ -173,6 +215,45 @@ impl<'info> Withdraw<'info> {
         Ok(())
     }
 
+    /// Create the empty temporary wSOL account receiving the native side, if given
+    pub fn open_native(&self, bump: Option<u8>) -> Result<()> {
+        let (Some(wsol_temp), Some(bump)) = (&self.wsol_temp, bump) else {
+            return Ok(());
+        };
+        let config = self.config.load()?;
+        require!(
+            is_native(&config.mint_x) || is_native(&config.mint_y),
+            AmmError::InvalidToken
+        );
+        let native_mint = self
+            .native_mint
+            .as_ref()
+            .ok_or(AmmError::MissingTokenAccount)?;
+
+        open_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            bump,
+            &native_mint.to_account_info(),
+            0,
+            &self.system_program.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+
+    /// Close the temporary wSOL account, if given, so the user receives native SOL
+    pub fn unwrap_native(&self) -> Result<()> {
+        let Some(wsol_temp) = &self.wsol_temp else {
+            return Ok(());
+        };
+
+        close_temp_wsol(
+            &self.user.to_account_info(),
+            &wsol_temp.to_account_info(),
+            &self.token_program.to_account_info(),
+        )
+    }
+
     /// Burn LP tokens from user
     pub fn burn_lp_tokens(&self, lp_amount: u64) -> Result<()> {
         let burn_ctx = CpiContext::new(
@@ -199,7 +280,7 @@ pub fn withdraw_handler(
     // validate inputs
     ctx.accounts.validate(lp_amount)?;
 
-    let config_bump = ctx.accounts.config.config_bump;
+    let config_bump = ctx.accounts.config.load()?.config_bump;
 
     // calculate withdraw amounts
     let (amount_x, amount_y) = ctx.accounts.calculate_withdraw_amounts(lp_amount)?;
@@ -211,9 +292,13 @@ pub fn withdraw_handler(
     // burn LP tokens first
     ctx.accounts.burn_lp_tokens(lp_amount)?;
 
-    // transfer tokens from vaults to user
+    // transfer tokens from vaults to user, through the temporary wSOL account when opted in
+    ctx.accounts.open_native(ctx.bumps.wsol_temp)?;
     ctx.accounts
         .transfer_from_vaults(amount_x, amount_y, config_bump)?;
 
+    // hand back native SOL from the temporary wSOL account, if any
+    ctx.accounts.unwrap_native()?;
+
     Ok(())
 }
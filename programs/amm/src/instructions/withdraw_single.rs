@@ -0,0 +1,241 @@
+use crate::{constants::*, error::AmmError, state::Config};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+#[instruction(lp_amount: u64, is_x_out: bool)]
+pub struct WithdrawSingle<'info> {
+    /// User withdrawing liquidity
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// AMM config account
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.config_bump,
+        constraint = !config.load()?.is_paused(PAUSE_WITHDRAW) @ AmmError::WithdrawalsPaused,
+        constraint = !config.load()?.is_paused(PAUSE_SWAP) @ AmmError::SwapsPaused,
+        constraint = !config.load()?.is_flash_loan_active() @ AmmError::FlashLoanActive,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// First token mint
+    #[account(
+        constraint = mint_x.key() == config.load()?.mint_x @ AmmError::InvalidToken
+    )]
+    pub mint_x: Account<'info, Mint>,
+
+    /// Second token mint
+    #[account(
+        constraint = mint_y.key() == config.load()?.mint_y @ AmmError::InvalidToken
+    )]
+    pub mint_y: Account<'info, Mint>,
+
+    /// LP token mint
+    #[account(
+        mut,
+        seeds = [LP_MINT_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump = config.load()?.lp_bump,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// User's token account for the requested output token
+    #[account(
+        mut,
+        token::authority = user,
+        constraint = user_out.mint == if is_x_out { config.load()?.mint_x } else { config.load()?.mint_y } @ AmmError::InvalidToken
+    )]
+    pub user_out: Account<'info, TokenAccount>,
+
+    /// User's LP token account
+    #[account(
+        mut,
+        associated_token::mint = lp_mint,
+        associated_token::authority = user
+    )]
+    pub user_lp: Account<'info, TokenAccount>,
+
+    /// Vault for token X
+    #[account(
+        mut,
+        seeds = [VAULT_X_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint_x,
+        token::authority = config,
+    )]
+    pub vault_x: Account<'info, TokenAccount>,
+
+    /// Vault for token Y
+    #[account(
+        mut,
+        seeds = [VAULT_Y_SEED.as_bytes(), config.load()?.seed.to_le_bytes().as_ref()],
+        bump,
+        token::mint = mint_y,
+        token::authority = config,
+    )]
+    pub vault_y: Account<'info, TokenAccount>,
+
+    /// SPL token program
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawSingle<'info> {
+    /// Validate single-sided withdraw parameters
+    pub fn validate(&self, lp_amount: u64) -> Result<()> {
+        // check lp amount is positive
+        require!(lp_amount > 0, AmmError::InvalidAmount);
+
+        // check user has sufficient LP tokens
+        require!(
+            self.user_lp.amount >= lp_amount,
+            AmmError::InsufficientBalance
+        );
+
+        // check pool has liquidity
+        require!(
+            self.vault_x.amount > 0 && self.vault_y.amount > 0,
+            AmmError::ZeroBalance
+        );
+        require!(self.lp_mint.supply > 0, AmmError::ZeroBalance);
+
+        // the swap leg needs liquidity left behind to trade against
+        require!(
+            lp_amount < self.lp_mint.supply,
+            AmmError::EntireSupplyWithdraw
+        );
+
+        Ok(())
+    }
+
+    /// Calculate the single token amount paid out for `lp_amount`
+    ///
+    /// Both sides are withdrawn proportionally, then the unwanted side is
+    /// swapped back into the pool against the reduced reserves.
+    pub fn calculate_withdraw_amount(&self, lp_amount: u64, is_x_out: bool) -> Result<u64> {
+        let reserve_x = self.vault_x.amount;
+        let reserve_y = self.vault_y.amount;
+        let total_supply = self.lp_mint.supply;
+
+        // calculate proportional amounts
+        let amount_x = (lp_amount as u128)
+            .checked_mul(reserve_x as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(total_supply as u128)
+            .ok_or(AmmError::ZeroBalance)? as u64;
+
+        let amount_y = (lp_amount as u128)
+            .checked_mul(reserve_y as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(total_supply as u128)
+            .ok_or(AmmError::ZeroBalance)? as u64;
+
+        require!(
+            amount_x > 0 && amount_y > 0,
+            AmmError::LiquidityLessThanMinimum
+        );
+
+        // reserves left in the pool after the proportional withdrawal
+        let remaining_x = reserve_x.checked_sub(amount_x).ok_or(AmmError::Underflow)?;
+        let remaining_y = reserve_y.checked_sub(amount_y).ok_or(AmmError::Underflow)?;
+
+        // swap the unwanted side through the curve (fee applies here)
+        let (amount_kept, amount_in, reserve_in, reserve_out) = if is_x_out {
+            (amount_x, amount_y, remaining_y, remaining_x)
+        } else {
+            (amount_y, amount_x, remaining_x, remaining_y)
+        };
+        let config = self.config.load()?;
+        let swapped_out = config.calculate_amount_out(amount_in, reserve_in, reserve_out)?;
+
+        // the swap leg is bound by the same pool-level limits as a regular swap
+        config.check_swap_limits(amount_in, swapped_out, reserve_in, reserve_out)?;
+
+        Ok(amount_kept
+            .checked_add(swapped_out)
+            .ok_or(AmmError::Overflow)?)
+    }
+
+    /// Transfer the output token from its vault to user
+    pub fn transfer_from_vault(
+        &self,
+        is_x_out: bool,
+        amount_out: u64,
+        config_bump: u8,
+    ) -> Result<()> {
+        let seed = self.config.load()?.seed.to_le_bytes();
+        let seeds = &[CONFIG_SEED.as_bytes(), seed.as_ref(), &[config_bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        let from = if is_x_out {
+            self.vault_x.to_account_info()
+        } else {
+            self.vault_y.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from,
+                to: self.user_out.to_account_info(),
+                authority: self.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount_out)
+    }
+
+    /// Burn LP tokens from user
+    pub fn burn_lp_tokens(&self, lp_amount: u64) -> Result<()> {
+        let burn_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            token::Burn {
+                mint: self.lp_mint.to_account_info(),
+                from: self.user_lp.to_account_info(),
+                authority: self.user.to_account_info(),
+            },
+        );
+
+        token::burn(burn_ctx, lp_amount)?;
+
+        Ok(())
+    }
+}
+
+pub fn withdraw_single_handler(
+    ctx: Context<WithdrawSingle>,
+    lp_amount: u64,
+    is_x_out: bool,
+    min_out: u64,
+) -> Result<()> {
+    // validate inputs
+    ctx.accounts.validate(lp_amount)?;
+
+    let config_bump = ctx.accounts.config.load()?.config_bump;
+
+    // calculate single-sided output (proportional share + swapped side)
+    let amount_out = ctx
+        .accounts
+        .calculate_withdraw_amount(lp_amount, is_x_out)?;
+
+    // slippage check
+    require!(amount_out >= min_out, AmmError::SlippageExceeded);
+
+    // the proportional part keeps the price, so it is measured from the current reserves
+    let before = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+
+    // burn LP tokens first
+    ctx.accounts.burn_lp_tokens(lp_amount)?;
+
+    // the swapped side never leaves its vault, only the output side is paid out
+    ctx.accounts
+        .transfer_from_vault(is_x_out, amount_out, config_bump)?;
+
+    // the swapped side moves the price, track it for the dynamic fee
+    ctx.accounts.vault_x.reload()?;
+    ctx.accounts.vault_y.reload()?;
+    let after = (ctx.accounts.vault_x.amount, ctx.accounts.vault_y.amount);
+    ctx.accounts.config.load_mut()?.record_swap(before, after)?;
+
+    Ok(())
+}
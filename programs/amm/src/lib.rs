@@ -3,7 +3,9 @@
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod instructions;
+pub mod native_sol;
 pub mod state;
 
 use anchor_lang::prelude::*;
@@ -18,14 +20,54 @@ declare_id!("GcVc8WR7EiPBuDiHGWFCRB8Tpjcqr7d4Jur3uLs1Fs1u");
 pub mod amm {
     use super::*;
 
+    /// Create the global factory
+    pub fn initialize_factory(
+        ctx: Context<InitializeFactory>,
+        protocol_fee: u16,
+        fee_tiers: Vec<u16>,
+    ) -> Result<()> {
+        instructions::initialize_factory::initialize_factory_handler(ctx, protocol_fee, fee_tiers)
+    }
+
+    /// Update global factory settings
+    pub fn update_factory(
+        ctx: Context<UpdateFactory>,
+        new_admin: Pubkey,
+        protocol_fee: u16,
+    ) -> Result<()> {
+        instructions::update_factory::update_factory_handler(ctx, new_admin, protocol_fee)
+    }
+
+    /// Whitelist a fee tier for new pools
+    pub fn add_fee_tier(ctx: Context<ManageFeeTiers>, fee: u16) -> Result<()> {
+        instructions::fee_tiers::add_fee_tier_handler(ctx, fee)
+    }
+
+    /// Remove a whitelisted fee tier
+    pub fn remove_fee_tier(ctx: Context<ManageFeeTiers>, fee: u16) -> Result<()> {
+        instructions::fee_tiers::remove_fee_tier_handler(ctx, fee)
+    }
+
     /// Initialize a new AMM pool
     pub fn initialize(ctx: Context<Initialize>, seed: u64, fee: u16) -> Result<()> {
         instructions::initialize::initialize_handler(ctx, seed, fee)
     }
 
-    /// Deposit liquidity to the pool
-    pub fn deposit(ctx: Context<Deposit>, amount_x: u64, amount_y: u64, min_lp: u64) -> Result<()> {
-        instructions::deposit::deposit_handler(ctx, amount_x, amount_y, min_lp)
+    /// Create Metaplex metadata for the LP mint, named "AMM LP X-Y" after the mints' symbols
+    pub fn create_lp_metadata(ctx: Context<CreateLpMetadata>, uri: String) -> Result<()> {
+        instructions::create_lp_metadata::create_lp_metadata_handler(ctx, uri)
+    }
+
+    /// Deposit liquidity to the pool, using between `min_x`..`max_x` and `min_y`..`max_y`
+    pub fn deposit(
+        ctx: Context<Deposit>,
+        max_x: u64,
+        max_y: u64,
+        min_lp: u64,
+        min_x: u64,
+        min_y: u64,
+    ) -> Result<()> {
+        instructions::deposit::deposit_handler(ctx, max_x, max_y, min_lp, min_x, min_y)
     }
 
     /// Withdraw liquidity from the pool
@@ -33,8 +75,177 @@ pub mod amm {
         instructions::withdraw::withdraw_handler(ctx, lp_amount, min_x, min_y)
     }
 
+    /// Withdraw liquidity from the pool as a single token
+    pub fn withdraw_single(
+        ctx: Context<WithdrawSingle>,
+        lp_amount: u64,
+        is_x_out: bool,
+        min_out: u64,
+    ) -> Result<()> {
+        instructions::withdraw_single::withdraw_single_handler(ctx, lp_amount, is_x_out, min_out)
+    }
+
+    /// Redeem LP tokens pro-rata once withdrawals have been paused past the grace period
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>, lp_amount: u64) -> Result<()> {
+        instructions::emergency_withdraw::emergency_withdraw_handler(ctx, lp_amount)
+    }
+
+    /// Swap through a chain of pools passed in remaining accounts
+    pub fn route_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RouteSwap<'info>>,
+        amount_in: u64,
+        min_out: u64,
+        hops: u8,
+    ) -> Result<()> {
+        instructions::route_swap::route_swap_handler(ctx, amount_in, min_out, hops)
+    }
+
+    /// Execute independent swaps across pools passed in remaining accounts
+    pub fn batch_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSwap<'info>>,
+        legs: Vec<SwapLeg>,
+        all_or_nothing: bool,
+    ) -> Result<()> {
+        instructions::batch_swap::batch_swap_handler(ctx, legs, all_or_nothing)
+    }
+
+    /// Close an empty pool, sweeping leftover vault tokens and rent to the authority
+    pub fn close_pool(ctx: Context<ClosePool>) -> Result<()> {
+        instructions::close_pool::close_pool_handler(ctx)
+    }
+
+    /// Rewrite a config created before layout versioning into the current layout
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        instructions::migrate_config::migrate_config_handler(ctx)
+    }
+
+    /// Configure the volatility-based dynamic fee
+    pub fn set_dynamic_fee(
+        ctx: Context<SetDynamicFee>,
+        enabled: bool,
+        min_fee: u16,
+        max_fee: u16,
+        volatility_factor: u16,
+        decay_period: i64,
+    ) -> Result<()> {
+        instructions::set_dynamic_fee::set_dynamic_fee_handler(
+            ctx,
+            enabled,
+            min_fee,
+            max_fee,
+            volatility_factor,
+            decay_period,
+        )
+    }
+
+    /// Pause or resume operations, `paused` is a combination of the `PAUSE_*` flags
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        instructions::set_pause::set_pause_handler(ctx, paused)
+    }
+
+    /// Set the per-swap price impact and trade size limits
+    pub fn set_swap_limits(
+        ctx: Context<SetSwapLimits>,
+        max_price_impact: u16,
+        max_trade_size: u16,
+    ) -> Result<()> {
+        instructions::set_swap_limits::set_swap_limits_handler(
+            ctx,
+            max_price_impact,
+            max_trade_size,
+        )
+    }
+
+    /// Configure the circuit breaker that pauses swaps on abnormal price moves
+    pub fn set_circuit_breaker(
+        ctx: Context<ManageCircuitBreaker>,
+        threshold: u32,
+        window: i64,
+    ) -> Result<()> {
+        instructions::manage_circuit_breaker::set_circuit_breaker_handler(ctx, threshold, window)
+    }
+
+    /// Reset a tripped circuit breaker and resume swaps
+    pub fn reset_circuit_breaker(ctx: Context<ManageCircuitBreaker>) -> Result<()> {
+        instructions::manage_circuit_breaker::reset_circuit_breaker_handler(ctx)
+    }
+
+    /// Lend vault tokens to a receiver program, repaid with a fee in the same instruction
+    pub fn flash_loan<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FlashLoan<'info>>,
+        amount_x: u64,
+        amount_y: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash_loan::flash_loan_handler(ctx, amount_x, amount_y, data)
+    }
+
+    /// Send swap output first, invoke a callback, then check x * y = k on the final balances
+    pub fn flash_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FlashSwap<'info>>,
+        amount_x_out: u64,
+        amount_y_out: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::flash_swap::flash_swap_handler(ctx, amount_x_out, amount_y_out, data)
+    }
+
+    /// Create a liquidity mining farm paying `reward_rate` reward tokens per second to LP stakers
+    pub fn initialize_farm(
+        ctx: Context<InitializeFarm>,
+        reward_authority: Pubkey,
+        reward_rate: u64,
+    ) -> Result<()> {
+        instructions::initialize_farm::initialize_farm_handler(ctx, reward_authority, reward_rate)
+    }
+
+    /// Add rewards to a farm, only rewards funded here are ever emitted
+    pub fn fund_farm(ctx: Context<FundFarm>, amount: u64) -> Result<()> {
+        instructions::fund_farm::fund_farm_handler(ctx, amount)
+    }
+
+    /// Change a farm's emission rate
+    pub fn set_reward_rate(ctx: Context<SetRewardRate>, reward_rate: u64) -> Result<()> {
+        instructions::set_reward_rate::set_reward_rate_handler(ctx, reward_rate)
+    }
+
+    /// Stake LP tokens in a farm
+    pub fn stake_lp(ctx: Context<StakeLp>, amount: u64) -> Result<()> {
+        instructions::stake_lp::stake_lp_handler(ctx, amount)
+    }
+
+    /// Unstake LP tokens from a farm
+    pub fn unstake_lp(ctx: Context<UnstakeLp>, amount: u64) -> Result<()> {
+        instructions::unstake_lp::unstake_lp_handler(ctx, amount)
+    }
+
+    /// Claim the rewards earned by a stake position
+    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+        instructions::claim_rewards::claim_rewards_handler(ctx)
+    }
+
+    /// Lock LP tokens until `unlock_ts`, vesting linearly from now when `linear` is set
+    pub fn lock_lp(ctx: Context<LockLp>, amount: u64, unlock_ts: i64, linear: bool) -> Result<()> {
+        instructions::lock_lp::lock_lp_handler(ctx, amount, unlock_ts, linear)
+    }
+
+    /// Release the vested LP tokens of a lock
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        instructions::unlock_lp::unlock_lp_handler(ctx)
+    }
+
     /// Swap tokens using constant product curve
     pub fn swap(ctx: Context<Swap>, is_x_to_y: bool, amount_in: u64, min_out: u64) -> Result<()> {
         instructions::swap::swap_handler(ctx, is_x_to_y, amount_in, min_out)
     }
+
+    /// Swap without the mint accounts, the direction follows the input token account's mint
+    pub fn swap_v2(ctx: Context<SwapV2>, amount_in: u64, min_out: u64) -> Result<()> {
+        instructions::swap_v2::swap_v2_handler(ctx, amount_in, min_out)
+    }
+
+    /// Swap into an output ATA created on demand, paying or receiving native SOL through a temporary wSOL account
+    pub fn swap_v3(ctx: Context<SwapV3>, amount_in: u64, min_out: u64) -> Result<()> {
+        instructions::swap_v3::swap_v3_handler(ctx, amount_in, min_out)
+    }
 }
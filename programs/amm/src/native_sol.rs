@@ -0,0 +1,109 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Allocate, Assign, CreateAccount, Transfer},
+};
+use anchor_spl::token::{
+    self, spl_token::native_mint, CloseAccount, InitializeAccount3, TokenAccount,
+};
+
+use crate::{constants::WSOL_TEMP_SEED, error::AmmError};
+
+/// Check if a mint is the wrapped SOL mint
+pub fn is_native(mint: &Pubkey) -> bool {
+    *mint == native_mint::ID
+}
+
+/// Create the user's temporary wSOL account at its PDA, wrapping `amount` lamports from the user
+///
+/// The account is owned by `user` so it can pay into the pool like any token account,
+/// and must be closed with `close_temp_wsol` before the instruction returns.
+pub(crate) fn open_temp_wsol<'info>(
+    user: &AccountInfo<'info>,
+    wsol_temp: &AccountInfo<'info>,
+    bump: u8,
+    mint: &AccountInfo<'info>,
+    amount: u64,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(is_native(mint.key), AmmError::InvalidToken);
+
+    let seeds = &[WSOL_TEMP_SEED.as_bytes(), user.key.as_ref(), &[bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // a native token account holds its lamports above rent as wrapped SOL
+    let lamports = Rent::get()?
+        .minimum_balance(TokenAccount::LEN)
+        .checked_add(amount)
+        .ok_or(AmmError::Overflow)?;
+
+    let current = wsol_temp.lamports();
+    if current == 0 {
+        let create_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: user.clone(),
+                to: wsol_temp.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::create_account(create_ctx, lamports, TokenAccount::LEN as u64, &token::ID)?;
+    } else {
+        // lamports sent to the address beforehand would make create_account fail
+        if lamports > current {
+            let transfer_ctx = CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: user.clone(),
+                    to: wsol_temp.clone(),
+                },
+            );
+            system_program::transfer(transfer_ctx, lamports - current)?;
+        }
+
+        let allocate_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: wsol_temp.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::allocate(allocate_ctx, TokenAccount::LEN as u64)?;
+
+        let assign_ctx = CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: wsol_temp.clone(),
+            },
+            signer_seeds,
+        );
+        system_program::assign(assign_ctx, &token::ID)?;
+    }
+
+    let init_ctx = CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: wsol_temp.clone(),
+            mint: mint.clone(),
+            authority: user.clone(),
+        },
+    );
+    token::initialize_account3(init_ctx)
+}
+
+/// Close the user's temporary wSOL account, returning its balance and rent as lamports
+pub(crate) fn close_temp_wsol<'info>(
+    user: &AccountInfo<'info>,
+    wsol_temp: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let close_ctx = CpiContext::new(
+        token_program.clone(),
+        CloseAccount {
+            account: wsol_temp.clone(),
+            destination: user.clone(),
+            authority: user.clone(),
+        },
+    );
+    token::close_account(close_ctx)
+}
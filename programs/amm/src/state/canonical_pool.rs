@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+
+/// Canonical pool lookup for a token pair and fee tier
+///
+/// Derived from the sorted pair mints and the fee, so clients can find
+/// "the" pool for a pair without an off-chain registry.
+#[account]
+#[derive(InitSpace)]
+pub struct CanonicalPool {
+    /// Config account of the canonical pool
+    pub config: Pubkey,
+
+    /// Seed of the canonical pool config
+    pub seed: u64,
+
+    /// PDA bump for canonical pool account
+    pub bump: u8,
+}
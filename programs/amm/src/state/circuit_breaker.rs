@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FEE_BASIS_POINTS, MAX_VOLATILITY_BPS},
+    error::AmmError,
+};
+
+/// Circuit breaker settings and state
+///
+/// Each window records the pool price before its first swap. Once a swap moves
+/// the price more than `threshold` away from that reference, the pool pauses
+/// swaps until the authority resets the breaker or unpauses them.
+#[zero_copy]
+#[derive(Default)]
+pub struct CircuitBreaker {
+    /// Price (y per x, scaled by PRICE_PRECISION) before the first swap of the current window
+    pub reference_price: u128,
+
+    /// Window length in seconds
+    pub window: i64,
+
+    /// Timestamp the current window started
+    pub window_start: i64,
+
+    /// Move from the reference price in basis points that trips the breaker (0 = disabled)
+    pub threshold: u32,
+
+    /// Move of the latest price from the reference price, in basis points
+    pub price_move: u32,
+
+    /// Whether the breaker paused swaps (0 = not tripped)
+    pub tripped: u8,
+
+    /// Keeps the size a multiple of 16 bytes
+    pub padding: [u8; 7],
+}
+
+impl CircuitBreaker {
+    /// Validate circuit breaker parameters
+    pub fn validate(threshold: u32, window: i64) -> Result<()> {
+        require!(
+            threshold <= MAX_VOLATILITY_BPS && (threshold == 0 || window > 0),
+            AmmError::InvalidCircuitBreaker
+        );
+        Ok(())
+    }
+
+    /// Check if the breaker is armed
+    pub fn is_enabled(&self) -> bool {
+        self.threshold > 0
+    }
+
+    /// Check if the breaker paused swaps
+    pub fn is_tripped(&self) -> bool {
+        self.tripped != 0
+    }
+
+    /// Record the pool price around a swap, returns true when it trips the breaker
+    pub fn update(&mut self, price_before: u128, price_after: u128, now: i64) -> Result<bool> {
+        // a new window is measured from the price before its first swap
+        if now.saturating_sub(self.window_start) >= self.window {
+            self.window_start = now;
+            self.reference_price = price_before;
+        }
+
+        // move against the window's reference price in basis points
+        let move_bps = price_after
+            .abs_diff(self.reference_price)
+            .checked_mul(FEE_BASIS_POINTS as u128)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(self.reference_price)
+            .unwrap_or(0);
+        self.price_move = move_bps.min(MAX_VOLATILITY_BPS as u128) as u32;
+
+        // only the first swap past the threshold trips it
+        if self.price_move > self.threshold && !self.is_tripped() {
+            self.tripped = 1;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Clear the tracked window and the tripped state, keeping the settings
+    pub fn reset(&mut self) {
+        *self = CircuitBreaker {
+            threshold: self.threshold,
+            window: self.window,
+            ..CircuitBreaker::default()
+        };
+    }
+}
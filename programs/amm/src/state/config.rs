@@ -1,16 +1,64 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::MAX_FEE_BASIS_POINTS, error::AmmError};
+use crate::{
+    constants::{
+        CONFIG_RESERVED_SPACE, EMERGENCY_WITHDRAW_DELAY, EMERGENCY_WITHDRAW_RESET,
+        FEE_BASIS_POINTS, MAX_FEE_BASIS_POINTS, PAUSE_SWAP, PAUSE_WITHDRAW, PRICE_PRECISION,
+    },
+    error::AmmError,
+    events::CircuitBreakerTripped,
+    state::{CircuitBreaker, DynamicFee},
+};
 
 /// AMM Pool Configuration
-#[account]
-#[derive(InitSpace)]
+///
+/// Zero-copy layout: fields are ordered so that no implicit padding is needed.
+#[account(zero_copy)]
 pub struct Config {
+    /// Account layout version, `CONFIG_VERSION` once created or migrated
+    pub version: u8,
+
+    /// Paused operations, a combination of the `PAUSE_*` flags
+    pub paused: u8,
+
+    /// Reentrancy guard set while a flash loan is outstanding (0 = none)
+    pub flash_loan_active: u8,
+
+    /// Whether this is the canonical pool for its pair and fee tier (0 = not canonical)
+    pub canonical: u8,
+
+    /// PDA bump for config account
+    pub config_bump: u8,
+
+    /// PDA bump for LP mint account
+    pub lp_bump: u8,
+
+    /// Trading fee in basis points (100 = 1%)
+    pub fee: u16,
+
+    /// Maximum spot price movement per swap in basis points (0 = no limit)
+    pub max_price_impact: u16,
+
+    /// Maximum swap input in basis points of the input reserve (0 = no limit)
+    pub max_trade_size: u16,
+
+    /// Aligns `seed` to 8 bytes
+    pub padding: [u8; 4],
+
     /// Unique identifier for this pool
     pub seed: u64,
 
-    /// Optional authority that can be manage pool settings
-    pub authority: Option<Pubkey>,
+    /// Slot at which the current withdrawal pause started, kept across short unpauses
+    pub withdraw_paused_slot: u64,
+
+    /// Optional volatility-based fee that overrides `fee` when enabled
+    pub dynamic_fee: DynamicFee,
+
+    /// Pauses swaps when the price moves too far within a time window
+    pub circuit_breaker: CircuitBreaker,
+
+    /// Authority that can manage pool settings (default pubkey = none)
+    pub authority: Pubkey,
 
     /// First token mint address
     pub mint_x: Pubkey,
@@ -18,23 +66,66 @@ pub struct Config {
     /// Second token mint address
     pub mint_y: Pubkey,
 
-    /// Trading fee in basis points (100 = 1%)
-    pub fee: u16,
-
-    /// Pool lock status (true = trading disabled)
-    pub locked: bool,
+    /// Slot at which withdrawals were last unpaused
+    pub withdraw_resumed_slot: u64,
 
-    /// PDA bump for config account
-    pub config_bump: u8,
-
-    /// PDA bump for LP mint account
-    pub lp_bump: u8,
+    /// Zeroed space for future fields, so they can be added without a realloc
+    pub reserved: [u8; CONFIG_RESERVED_SPACE],
 }
 
 impl Config {
-    /// Check if pool is currently locked
-    pub fn is_locked(&self) -> bool {
-        self.locked
+    /// Account size without the discriminator
+    pub const LEN: usize = std::mem::size_of::<Config>();
+
+    /// LP mint decimals for a pair, the LP supply starts at sqrt(x * y) so its scale is the mean
+    pub fn lp_decimals(decimals_x: u8, decimals_y: u8) -> u8 {
+        ((decimals_x as u16 + decimals_y as u16) / 2) as u8
+    }
+
+    /// Check if a flash loan is outstanding
+    pub fn is_flash_loan_active(&self) -> bool {
+        self.flash_loan_active != 0
+    }
+
+    /// Check if this is the canonical pool for its pair and fee tier
+    pub fn is_canonical(&self) -> bool {
+        self.canonical != 0
+    }
+
+    /// Check if any of the given operations is paused
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    /// Update the paused operations, recording when withdrawals become paused
+    ///
+    /// A new withdrawal pause only restarts the emergency delay once withdrawals have stayed
+    /// unpaused for `EMERGENCY_WITHDRAW_RESET` slots, so re-pausing cannot hold LPs off forever.
+    /// Resuming swaps also resets the circuit breaker, so it does not trip again on the
+    /// window that paused them.
+    pub fn set_paused(&mut self, paused: u8, slot: u64) {
+        if paused & PAUSE_WITHDRAW != 0 && !self.is_paused(PAUSE_WITHDRAW) {
+            let resumed_for = slot.saturating_sub(self.withdraw_resumed_slot);
+            if self.withdraw_resumed_slot == 0 || resumed_for >= EMERGENCY_WITHDRAW_RESET {
+                self.withdraw_paused_slot = slot;
+            }
+        }
+        if paused & PAUSE_WITHDRAW == 0 && self.is_paused(PAUSE_WITHDRAW) {
+            self.withdraw_resumed_slot = slot;
+        }
+        if paused & PAUSE_SWAP == 0 && self.is_paused(PAUSE_SWAP) {
+            self.circuit_breaker.reset();
+        }
+        self.paused = paused;
+    }
+
+    /// Check if withdrawals have been paused for longer than the emergency grace period
+    pub fn can_emergency_withdraw(&self, slot: u64) -> bool {
+        self.is_paused(PAUSE_WITHDRAW)
+            && slot
+                >= self
+                    .withdraw_paused_slot
+                    .saturating_add(EMERGENCY_WITHDRAW_DELAY)
     }
 
     /// Validate fee is within acceptable range
@@ -45,10 +136,8 @@ impl Config {
 
     /// Check if given authority can modify pool settings
     pub fn can_modify(&self, authority: &Pubkey) -> Result<()> {
-        match self.authority {
-            Some(auth) => require!(auth == *authority, AmmError::Unauthorized),
-            None => return Err(AmmError::NoAuthority.into()),
-        }
+        require!(self.authority != Pubkey::default(), AmmError::NoAuthority);
+        require!(self.authority == *authority, AmmError::Unauthorized);
         Ok(())
     }
 
@@ -57,4 +146,202 @@ impl Config {
         require!(reserve_x > 0 && reserve_y > 0, AmmError::ZeroBalance);
         Ok(reserve_x as f64 / reserve_y as f64)
     }
+
+    /// Validate swap limits, both are basis points of at most 100%
+    pub fn validate_swap_limits(max_price_impact: u16, max_trade_size: u16) -> Result<()> {
+        let denom_bps = FEE_BASIS_POINTS as u16;
+        require!(
+            max_price_impact <= denom_bps && max_trade_size <= denom_bps,
+            AmmError::InvalidSwapLimit
+        );
+        Ok(())
+    }
+
+    /// Enforce the pool's trade size and price impact limits on a quoted swap
+    pub fn check_swap_limits(
+        &self,
+        amount_in: u64,
+        amount_out: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<()> {
+        let denom_bps = FEE_BASIS_POINTS as u128;
+
+        // amount_in <= reserve_in * max_trade_size / 10000
+        if self.max_trade_size > 0 {
+            require!(
+                (amount_in as u128)
+                    .checked_mul(denom_bps)
+                    .ok_or(AmmError::Overflow)?
+                    <= (reserve_in as u128)
+                        .checked_mul(self.max_trade_size as u128)
+                        .ok_or(AmmError::Overflow)?,
+                AmmError::TradeSizeExceeded
+            );
+        }
+
+        // spot price out/in moves from reserve_out / reserve_in to
+        // (reserve_out - amount_out) / (reserve_in + amount_in), so the relative move is
+        // (reserve_out * amount_in + amount_out * reserve_in) / (reserve_out * (reserve_in + amount_in))
+        if self.max_price_impact > 0 {
+            let moved = (reserve_out as u128)
+                .checked_mul(amount_in as u128)
+                .ok_or(AmmError::Overflow)?
+                .checked_add(
+                    (amount_out as u128)
+                        .checked_mul(reserve_in as u128)
+                        .ok_or(AmmError::Overflow)?,
+                )
+                .ok_or(AmmError::Overflow)?;
+            let spot = (reserve_out as u128)
+                .checked_mul(
+                    (reserve_in as u128)
+                        .checked_add(amount_in as u128)
+                        .ok_or(AmmError::Overflow)?,
+                )
+                .ok_or(AmmError::Overflow)?;
+
+            require!(
+                moved.checked_mul(denom_bps).ok_or(AmmError::Overflow)?
+                    <= spot
+                        .checked_mul(self.max_price_impact as u128)
+                        .ok_or(AmmError::Overflow)?,
+                AmmError::PriceImpactExceeded
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Effective swap fee in basis points at `now`
+    pub fn swap_fee(&self, now: i64) -> u16 {
+        if self.dynamic_fee.is_enabled() {
+            self.dynamic_fee.current_fee(now)
+        } else {
+            self.fee
+        }
+    }
+
+    /// Pool price (y per x, scaled by PRICE_PRECISION)
+    pub fn spot_price(reserve_x: u64, reserve_y: u64) -> Result<u128> {
+        require!(reserve_x > 0, AmmError::ZeroBalance);
+
+        Ok((reserve_y as u128)
+            .checked_mul(PRICE_PRECISION)
+            .ok_or(AmmError::Overflow)?
+            / (reserve_x as u128))
+    }
+
+    /// Order the reserves (x, y) as (in, out) for a swap direction
+    pub fn reserves_in_out(is_x_to_y: bool, reserve_x: u64, reserve_y: u64) -> (u64, u64) {
+        if is_x_to_y {
+            (reserve_x, reserve_y)
+        } else {
+            (reserve_y, reserve_x)
+        }
+    }
+
+    /// Pool reserves (x, y) once a swap of `amount_in` for `amount_out` has settled
+    pub fn post_swap_reserves(
+        is_x_to_y: bool,
+        reserve_x: u64,
+        reserve_y: u64,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<(u64, u64)> {
+        let (reserve_in, reserve_out) = Self::reserves_in_out(is_x_to_y, reserve_x, reserve_y);
+        let new_in = reserve_in
+            .checked_add(amount_in)
+            .ok_or(AmmError::Overflow)?;
+        let new_out = reserve_out
+            .checked_sub(amount_out)
+            .ok_or(AmmError::Underflow)?;
+
+        Ok(Self::reserves_in_out(is_x_to_y, new_in, new_out))
+    }
+
+    /// Record the reserves (x, y) before and after a swap for the dynamic fee and the circuit breaker
+    pub fn record_swap(&mut self, before: (u64, u64), after: (u64, u64)) -> Result<()> {
+        if !self.dynamic_fee.is_enabled() && !self.circuit_breaker.is_enabled() {
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let (reserve_x, reserve_y) = after;
+
+        if self.dynamic_fee.is_enabled() {
+            self.dynamic_fee.update(reserve_x, reserve_y, now)?;
+        }
+
+        // the tripping swap itself goes through, later swaps see the pause
+        if self.circuit_breaker.is_enabled() {
+            let price_before = Self::spot_price(before.0, before.1)?;
+            let price_after = Self::spot_price(reserve_x, reserve_y)?;
+
+            if self
+                .circuit_breaker
+                .update(price_before, price_after, now)?
+            {
+                self.paused |= PAUSE_SWAP;
+                emit!(CircuitBreakerTripped {
+                    seed: self.seed,
+                    price: price_after,
+                    price_move: self.circuit_breaker.price_move,
+                    timestamp: now,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Constant product with the effective fee: returns amount_out for the given reserves
+    pub fn calculate_amount_out(
+        &self,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<u64> {
+        let fee = self.swap_fee(Clock::get()?.unix_timestamp);
+        Self::calculate_amount_out_with_fee(fee, amount_in, reserve_in, reserve_out)
+    }
+
+    /// Constant product with fee: returns amount_out for the given reserves
+    pub fn calculate_amount_out_with_fee(
+        fee: u16,
+        amount_in: u64,
+        reserve_in: u64,
+        reserve_out: u64,
+    ) -> Result<u64> {
+        // amount_in_with_fee = amount_in * (FEE_BASIS_POINTS - fee)
+        let fee_bps = fee as u128;
+        let denom_bps = FEE_BASIS_POINTS as u128;
+
+        let amount_in_u128 = amount_in as u128;
+        let reserve_in_u128 = reserve_in as u128;
+        let reserve_out_u128 = reserve_out as u128;
+
+        let amount_in_with_fee = amount_in_u128
+            .checked_mul(denom_bps.checked_sub(fee_bps).ok_or(AmmError::Underflow)?)
+            .ok_or(AmmError::Overflow)?;
+
+        // numerator = amount_in_with_fee * reserve_out
+        let numerator = amount_in_with_fee
+            .checked_mul(reserve_out_u128)
+            .ok_or(AmmError::Overflow)?;
+
+        // denominator = reserve_in * denom_bps + amount_int_with_fee
+        let denominator = reserve_in_u128
+            .checked_mul(denom_bps)
+            .ok_or(AmmError::Overflow)?
+            .checked_add(amount_in_with_fee)
+            .ok_or(AmmError::Overflow)?;
+
+        let amount_out = numerator
+            .checked_div(denominator)
+            .ok_or(AmmError::ZeroBalance)? as u64;
+
+        require!(amount_out > 0, AmmError::SlippageExceeded);
+        Ok(amount_out)
+    }
 }
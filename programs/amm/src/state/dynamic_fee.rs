@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{FEE_BASIS_POINTS, MAX_FEE_BASIS_POINTS, MAX_VOLATILITY_BPS},
+    error::AmmError,
+    state::Config,
+};
+
+/// Volatility-based dynamic fee settings and state
+///
+/// Every swap adds the price move against the tracked reference to a
+/// volatility accumulator, which decays linearly to zero over `decay_period`.
+/// The swap fee is `min_fee + volatility * volatility_factor`, capped at `max_fee`.
+#[zero_copy]
+#[derive(Default)]
+pub struct DynamicFee {
+    /// Price (y per x, scaled by PRICE_PRECISION) after the last swap
+    pub reference_price: u128,
+
+    /// Seconds for the volatility accumulator to decay to zero
+    pub decay_period: i64,
+
+    /// Timestamp of the last swap
+    pub last_update_ts: i64,
+
+    /// Accumulated price movement in basis points
+    pub volatility: u32,
+
+    /// Lower fee bound in basis points
+    pub min_fee: u16,
+
+    /// Upper fee bound in basis points
+    pub max_fee: u16,
+
+    /// Fee added per basis point of volatility, in basis points
+    pub volatility_factor: u16,
+
+    /// Whether the dynamic fee replaces the static pool fee (0 = disabled)
+    pub enabled: u8,
+
+    /// Keeps the size a multiple of 16 bytes
+    pub padding: [u8; 5],
+}
+
+impl DynamicFee {
+    /// Check if the dynamic fee replaces the static pool fee
+    pub fn is_enabled(&self) -> bool {
+        self.enabled != 0
+    }
+
+    /// Validate dynamic fee parameters
+    pub fn validate(min_fee: u16, max_fee: u16, decay_period: i64) -> Result<()> {
+        require!(
+            min_fee <= max_fee && max_fee <= MAX_FEE_BASIS_POINTS,
+            AmmError::InvalidFee
+        );
+        require!(decay_period > 0, AmmError::InvalidAmount);
+        Ok(())
+    }
+
+    /// Volatility left after decaying since the last swap
+    pub fn decayed_volatility(&self, now: i64) -> u32 {
+        let elapsed = now.saturating_sub(self.last_update_ts).max(0);
+        if elapsed >= self.decay_period {
+            return 0;
+        }
+
+        // linear decay: volatility * (decay_period - elapsed) / decay_period
+        ((self.volatility as i128) * ((self.decay_period - elapsed) as i128)
+            / (self.decay_period as i128)) as u32
+    }
+
+    /// Effective swap fee in basis points at `now`
+    pub fn current_fee(&self, now: i64) -> u16 {
+        let increase = (self.decayed_volatility(now) as u64) * (self.volatility_factor as u64)
+            / (FEE_BASIS_POINTS as u64);
+        let fee = (self.min_fee as u64).saturating_add(increase);
+
+        fee.min(self.max_fee as u64) as u16
+    }
+
+    /// Record the pool price after a swap
+    pub fn update(&mut self, reserve_x: u64, reserve_y: u64, now: i64) -> Result<()> {
+        let price = Config::spot_price(reserve_x, reserve_y)?;
+
+        let mut volatility = self.decayed_volatility(now);
+
+        // add the move against the reference price in basis points
+        if self.reference_price > 0 {
+            let delta = price.abs_diff(self.reference_price);
+            let move_bps = delta
+                .checked_mul(FEE_BASIS_POINTS as u128)
+                .ok_or(AmmError::Overflow)?
+                / self.reference_price;
+
+            volatility = (volatility as u128)
+                .saturating_add(move_bps)
+                .min(MAX_VOLATILITY_BPS as u128) as u32;
+        }
+
+        self.volatility = volatility;
+        self.reference_price = price;
+        self.last_update_ts = now;
+
+        Ok(())
+    }
+}
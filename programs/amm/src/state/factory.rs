@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_FEE_BASIS_POINTS, MAX_FEE_TIERS, MAX_PROTOCOL_FEE_BASIS_POINTS},
+    error::AmmError,
+};
+
+/// Global AMM factory, created once
+#[account]
+#[derive(InitSpace)]
+pub struct Factory {
+    /// Global admin that manages factory settings
+    pub admin: Pubkey,
+
+    /// Default protocol fee as a share of trading fees in basis points
+    pub protocol_fee: u16,
+
+    /// Fee tiers allowed for new pools in basis points
+    #[max_len(MAX_FEE_TIERS)]
+    pub fee_tiers: Vec<u16>,
+
+    /// Number of pools created through this factory
+    pub pool_count: u64,
+
+    /// PDA bump for factory account
+    pub bump: u8,
+}
+
+impl Factory {
+    /// Check if given admin can modify factory settings
+    pub fn can_modify(&self, admin: &Pubkey) -> Result<()> {
+        require!(self.admin == *admin, AmmError::Unauthorized);
+        Ok(())
+    }
+
+    /// Validate protocol fee is within acceptable range
+    pub fn validate_protocol_fee(protocol_fee: u16) -> Result<()> {
+        require!(
+            protocol_fee <= MAX_PROTOCOL_FEE_BASIS_POINTS,
+            AmmError::InvalidFee
+        );
+        Ok(())
+    }
+
+    /// Validate a list of fee tiers
+    pub fn validate_fee_tiers(fee_tiers: &[u16]) -> Result<()> {
+        require!(fee_tiers.len() <= MAX_FEE_TIERS, AmmError::TooManyFeeTiers);
+
+        for (i, fee) in fee_tiers.iter().enumerate() {
+            require!(*fee <= MAX_FEE_BASIS_POINTS, AmmError::InvalidFee);
+            require!(!fee_tiers[..i].contains(fee), AmmError::DuplicateFeeTier);
+        }
+
+        Ok(())
+    }
+
+    /// Check if a fee is one of the whitelisted tiers
+    pub fn is_fee_tier_allowed(&self, fee: u16) -> bool {
+        self.fee_tiers.contains(&fee)
+    }
+
+    /// Whitelist a new fee tier
+    pub fn add_fee_tier(&mut self, fee: u16) -> Result<()> {
+        require!(fee <= MAX_FEE_BASIS_POINTS, AmmError::InvalidFee);
+        require!(!self.is_fee_tier_allowed(fee), AmmError::DuplicateFeeTier);
+        require!(
+            self.fee_tiers.len() < MAX_FEE_TIERS,
+            AmmError::TooManyFeeTiers
+        );
+
+        self.fee_tiers.push(fee);
+        Ok(())
+    }
+
+    /// Remove a whitelisted fee tier (existing pools keep their fee)
+    pub fn remove_fee_tier(&mut self, fee: u16) -> Result<()> {
+        let position = self
+            .fee_tiers
+            .iter()
+            .position(|tier| *tier == fee)
+            .ok_or(AmmError::FeeTierNotFound)?;
+
+        self.fee_tiers.remove(position);
+        Ok(())
+    }
+}
+
+/// Registry entry for a single pool, addressed by its creation index
+///
+/// Indexes are dense so pools can be listed by walking `0..pool_count`. A closed pool
+/// leaves its entry behind with a default `config`, which clients skip. The client
+/// derives the next address from `pool_count`, so of two concurrent `initialize`
+/// calls the later one fails with a seeds mismatch and must be retried.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolIndex {
+    /// Position of the pool in the factory registry
+    pub index: u64,
+
+    /// Config account of the registered pool (default pubkey = closed)
+    pub config: Pubkey,
+
+    /// PDA bump for pool index account
+    pub bump: u8,
+}
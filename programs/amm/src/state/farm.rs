@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::REWARD_PRECISION, error::AmmError};
+
+/// Liquidity mining rewards for one AMM pool and reward mint
+///
+/// Emissions accrue into a reward-per-share accumulator and are capped by
+/// the rewards the reward authority has funded, so the farm never owes more
+/// than its vault holds.
+#[account]
+#[derive(InitSpace)]
+pub struct Farm {
+    /// Config account of the pool whose LP tokens are staked
+    pub config: Pubkey,
+
+    /// LP token mint of the pool
+    pub lp_mint: Pubkey,
+
+    /// Token paid out as rewards
+    pub reward_mint: Pubkey,
+
+    /// Authority that funds the farm and sets the emission rate
+    pub reward_authority: Pubkey,
+
+    /// Reward tokens emitted per second across all stakers
+    pub reward_rate: u64,
+
+    /// Funded rewards not yet emitted
+    pub undistributed: u64,
+
+    /// Rewards per staked LP token, scaled by REWARD_PRECISION
+    pub acc_reward_per_share: u128,
+
+    /// LP tokens staked in the farm
+    pub total_staked: u64,
+
+    /// Timestamp emissions were last accrued at
+    pub last_update_ts: i64,
+
+    /// PDA bump for farm account
+    pub bump: u8,
+}
+
+impl Farm {
+    /// Accrue emissions since the last update into the reward-per-share accumulator
+    pub fn update(&mut self, now: i64) -> Result<()> {
+        if now <= self.last_update_ts {
+            return Ok(());
+        }
+        let elapsed = (now - self.last_update_ts) as u64;
+        self.last_update_ts = now;
+
+        // nothing is emitted while nobody stakes, the funding is kept for later
+        if self.total_staked == 0 {
+            return Ok(());
+        }
+
+        let reward = elapsed
+            .saturating_mul(self.reward_rate)
+            .min(self.undistributed);
+        if reward == 0 {
+            return Ok(());
+        }
+
+        let per_share = (reward as u128)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(AmmError::Overflow)?
+            .checked_div(self.total_staked as u128)
+            .ok_or(AmmError::Overflow)?;
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(per_share)
+            .ok_or(AmmError::Overflow)?;
+        self.undistributed -= reward;
+
+        Ok(())
+    }
+}
+
+/// A user's staked LP tokens in a farm
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    /// Farm the position belongs to
+    pub farm: Pubkey,
+
+    /// Owner of the staked LP tokens
+    pub owner: Pubkey,
+
+    /// LP tokens staked
+    pub amount: u64,
+
+    /// `amount * acc_reward_per_share` at the last settlement, scaled by REWARD_PRECISION
+    pub reward_debt: u128,
+
+    /// Settled rewards not yet claimed
+    pub pending: u64,
+
+    /// PDA bump for stake position account
+    pub bump: u8,
+}
+
+impl StakePosition {
+    /// Rewards accrued on the current stake since the position was opened, scaled
+    fn accrued(&self, acc_reward_per_share: u128) -> Result<u128> {
+        (self.amount as u128)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(AmmError::Overflow.into())
+    }
+
+    /// Move rewards earned since the last settlement into `pending`
+    pub fn settle(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        let accrued = self.accrued(acc_reward_per_share)?;
+        let earned = accrued
+            .checked_sub(self.reward_debt)
+            .ok_or(AmmError::Underflow)?
+            / REWARD_PRECISION;
+
+        self.pending = self
+            .pending
+            .checked_add(u64::try_from(earned).map_err(|_| AmmError::Overflow)?)
+            .ok_or(AmmError::Overflow)?;
+        // keep the rounded-off remainder owed rather than dropping it
+        self.reward_debt += earned * REWARD_PRECISION;
+
+        Ok(())
+    }
+
+    /// Change the staked amount right after `settle`, carrying its remainder over
+    pub fn set_amount(&mut self, amount: u64, acc_reward_per_share: u128) -> Result<()> {
+        // what `settle` kept owed, less than one reward token
+        let remainder = self
+            .accrued(acc_reward_per_share)?
+            .checked_sub(self.reward_debt)
+            .ok_or(AmmError::Underflow)?;
+
+        self.amount = amount;
+        // an emptied position has no stake left to carry the remainder on
+        self.reward_debt = self
+            .accrued(acc_reward_per_share)?
+            .saturating_sub(remainder);
+
+        Ok(())
+    }
+}
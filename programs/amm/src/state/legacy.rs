@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CONFIG_RESERVED_SPACE, CONFIG_VERSION, PAUSE_ALL},
+    state::{CircuitBreaker, Config, DynamicFee},
+};
+
+/// Unversioned Borsh `Config` layout of the pools deployed before `version` was added
+///
+/// Only read by `migrate_config`, never written. No other layout was ever deployed,
+/// so every legacy pool migrates from this one straight to the current layout.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct ConfigV0 {
+    pub seed: u64,
+    pub authority: Option<Pubkey>,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fee: u16,
+    pub locked: bool,
+    pub config_bump: u8,
+    pub lp_bump: u8,
+}
+
+impl ConfigV0 {
+    /// Convert to the current zero-copy layout, new features start disabled
+    pub fn migrate(self, slot: u64) -> Config {
+        // a locked pool blocked every operation, so it stays fully paused
+        let paused = if self.locked { PAUSE_ALL } else { 0 };
+
+        Config {
+            version: CONFIG_VERSION,
+            paused,
+            flash_loan_active: 0,
+            canonical: 0,
+            config_bump: self.config_bump,
+            lp_bump: self.lp_bump,
+            fee: self.fee,
+            max_price_impact: 0,
+            max_trade_size: 0,
+            padding: [0; 4],
+            seed: self.seed,
+            // the emergency withdrawal grace period starts at migration
+            withdraw_paused_slot: if self.locked { slot } else { 0 },
+            dynamic_fee: DynamicFee::default(),
+            circuit_breaker: CircuitBreaker::default(),
+            authority: self.authority.unwrap_or_default(),
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            withdraw_resumed_slot: 0,
+            reserved: [0; CONFIG_RESERVED_SPACE],
+        }
+    }
+}
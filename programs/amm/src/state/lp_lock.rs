@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+/// LP tokens escrowed until a timestamp, one lock per owner and pool
+///
+/// The escrowed tokens keep their share of the pool, so swap fees still
+/// accrue to them while locked.
+#[account]
+#[derive(InitSpace)]
+pub struct LpLock {
+    /// Config of the pool whose LP tokens are locked
+    pub config: Pubkey,
+
+    /// Owner that can unlock the tokens
+    pub owner: Pubkey,
+
+    /// LP tokens locked
+    pub amount: u64,
+
+    /// LP tokens already released
+    pub released: u64,
+
+    /// Timestamp the lock was created at, start of the vesting
+    pub start_ts: i64,
+
+    /// Timestamp everything is released at
+    pub unlock_ts: i64,
+
+    /// Release linearly between `start_ts` and `unlock_ts` instead of all at `unlock_ts`
+    pub linear: bool,
+
+    /// PDA bump for lock account
+    pub bump: u8,
+}
+
+impl LpLock {
+    /// LP tokens vested at a timestamp, released or not
+    pub fn vested(&self, now: i64) -> u64 {
+        if now >= self.unlock_ts {
+            return self.amount;
+        }
+        if !self.linear || now <= self.start_ts {
+            return 0;
+        }
+
+        // unlock_ts > now > start_ts, so the ratio stays below one
+        let elapsed = (now - self.start_ts) as u128;
+        let duration = (self.unlock_ts - self.start_ts) as u128;
+        (self.amount as u128 * elapsed / duration) as u64
+    }
+
+    /// LP tokens that can be released at a timestamp
+    pub fn releasable(&self, now: i64) -> u64 {
+        self.vested(now).saturating_sub(self.released)
+    }
+}
@@ -1,3 +1,17 @@
+pub mod canonical_pool;
+pub mod circuit_breaker;
 pub mod config;
+pub mod dynamic_fee;
+pub mod factory;
+pub mod farm;
+pub mod legacy;
+pub mod lp_lock;
 
+pub use canonical_pool::*;
+pub use circuit_breaker::*;
 pub use config::*;
+pub use dynamic_fee::*;
+pub use factory::*;
+pub use farm::*;
+pub use legacy::*;
+pub use lp_lock::*;
@@ -0,0 +1,168 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Amm } from "../target/types/amm";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("AMM Factory", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.amm as Program<Amm>;
+
+  // the factory is a singleton shared by every test suite
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+  // only the program's upgrade authority (the deployer) can create the factory
+  const [programDataPda] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111"),
+  );
+  const protocolFee = 1000; // 10% of trading fees
+  const feeTiers = [1, 5, 30, 100, 300];
+
+  it("Fails to initialize the factory without the upgrade authority", async () => {
+    const stranger = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, 1_000_000_000)
+    );
+
+    try {
+      await program.methods
+        .initializeFactory(protocolFee, feeTiers)
+        .accounts({
+          admin: stranger.publicKey,
+          programData: programDataPda,
+          factory: factoryPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc();
+
+      expect.fail("Should have failed with unauthorized admin.");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+  });
+
+  it("Successfully initializes the global factory", async () => {
+    const tx = await program.methods
+      .initializeFactory(protocolFee, feeTiers)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        programData: programDataPda,
+        factory: factoryPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    console.log("Initialize factory transaction signature: ", tx);
+
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    expect(factoryAccount.admin.toString()).to.equal(provider.wallet.publicKey.toString());
+    expect(factoryAccount.protocolFee).to.equal(protocolFee);
+    expect(factoryAccount.feeTiers).to.deep.equal(feeTiers);
+    expect(factoryAccount.poolCount.toNumber()).to.equal(0);
+  });
+
+  it("Fails to update factory from non-admin", async () => {
+    const stranger = Keypair.generate();
+
+    try {
+      await program.methods
+        .updateFactory(stranger.publicKey, protocolFee)
+        .accounts({
+          admin: stranger.publicKey,
+          factory: factoryPda,
+        })
+        .signers([stranger])
+        .rpc();
+
+      expect.fail("Should have failed with unauthorized admin.");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+  });
+
+  it("Updates the default protocol fee", async () => {
+    await program.methods
+      .updateFactory(provider.wallet.publicKey, 500)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        factory: factoryPda,
+      })
+      .rpc();
+
+    let factoryAccount = await program.account.factory.fetch(factoryPda);
+    expect(factoryAccount.protocolFee).to.equal(500);
+
+    // restore for the remaining suites
+    await program.methods
+      .updateFactory(provider.wallet.publicKey, protocolFee)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        factory: factoryPda,
+      })
+      .rpc();
+
+    factoryAccount = await program.account.factory.fetch(factoryPda);
+    expect(factoryAccount.protocolFee).to.equal(protocolFee);
+  });
+
+  it("Fails to set a protocol fee above the maximum", async () => {
+    try {
+      await program.methods
+        .updateFactory(provider.wallet.publicKey, 5001)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          factory: factoryPda,
+        })
+        .rpc();
+
+      expect.fail("Should have failed with invalid fee.");
+    } catch (error) {
+      expect(error.message).to.include("InvalidFee");
+    }
+  });
+
+  it("Adds and removes a fee tier", async () => {
+    await program.methods
+      .addFeeTier(50)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        factory: factoryPda,
+      })
+      .rpc();
+
+    let factoryAccount = await program.account.factory.fetch(factoryPda);
+    expect(factoryAccount.feeTiers).to.include(50);
+
+    await program.methods
+      .removeFeeTier(50)
+      .accounts({
+        admin: provider.wallet.publicKey,
+        factory: factoryPda,
+      })
+      .rpc();
+
+    factoryAccount = await program.account.factory.fetch(factoryPda);
+    expect(factoryAccount.feeTiers).to.deep.equal(feeTiers);
+  });
+
+  it("Fails to add a duplicate fee tier", async () => {
+    try {
+      await program.methods
+        .addFeeTier(30)
+        .accounts({
+          admin: provider.wallet.publicKey,
+          factory: factoryPda,
+        })
+        .rpc();
+
+      expect.fail("Should have failed with duplicate fee tier.");
+    } catch (error) {
+      expect(error.message).to.include("DuplicateFeeTier");
+    }
+  });
+});
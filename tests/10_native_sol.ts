@@ -0,0 +1,273 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  createMint,
+  mintTo,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+describe("AMM Native SOL", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let mintY: PublicKey;
+  let authority: Keypair;
+  let trader: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let vaultXPda: PublicKey;
+  let vaultYPda: PublicKey;
+  let seed: anchor.BN;
+  const fee = 30; // 0.3%
+
+  // program-owned temporary wSOL account, passing it opts in to paying or receiving native SOL
+  const wsolTemp = (owner: Keypair) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("wsol_temp"), owner.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    trader = Keypair.generate();
+
+    seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 10 * LAMPORTS_PER_SOL)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(trader.publicKey, 2 * LAMPORTS_PER_SOL)
+      ),
+    ]);
+
+    mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: NATIVE_MINT,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // seed liquidity with native SOL on the X side
+    const authY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority, mintY, authY.address, authority, 1_000_000_000);
+
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL), new anchor.BN(100_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: null,
+        userY: authY.address,
+        wsolTemp: wsolTemp(authority),
+        nativeMint: NATIVE_MINT,
+        userLp: getAssociatedTokenAddressSync(lpMintPda, authority.publicKey),
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+  });
+
+  it("Wraps SOL on deposit and closes the temporary wSOL account", async () => {
+    const vaultX = await provider.connection.getTokenAccountBalance(vaultXPda);
+    expect(Number(vaultX.value.amount)).to.equal(LAMPORTS_PER_SOL);
+
+    expect(await provider.connection.getAccountInfo(wsolTemp(authority))).to.be.null;
+    // no wSOL ATA is needed or created
+    const wsolAta = getAssociatedTokenAddressSync(NATIVE_MINT, authority.publicKey);
+    expect(await provider.connection.getAccountInfo(wsolAta)).to.be.null;
+  });
+
+  it("Swaps native SOL in and receives native SOL out", async () => {
+    const traderY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      trader,
+      mintY,
+      trader.publicKey
+    );
+
+    // SOL -> Y
+    await program.methods
+      .swapV3(new anchor.BN(LAMPORTS_PER_SOL / 10), new anchor.BN(1))
+      .accounts({
+        user: trader.publicKey,
+        config: configPda,
+        mintIn: NATIVE_MINT,
+        mintOut: mintY,
+        userIn: null,
+        userOut: traderY.address,
+        wsolTemp: wsolTemp(trader),
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([trader])
+      .rpc();
+
+    const yBalance = await provider.connection.getTokenAccountBalance(traderY.address);
+    expect(Number(yBalance.value.amount)).to.be.greaterThan(0);
+    expect(await provider.connection.getAccountInfo(wsolTemp(trader))).to.be.null;
+
+    // Y -> SOL
+    const solBefore = await provider.connection.getBalance(trader.publicKey);
+
+    await program.methods
+      .swapV3(new anchor.BN(yBalance.value.amount), new anchor.BN(1))
+      .accounts({
+        user: trader.publicKey,
+        config: configPda,
+        mintIn: mintY,
+        mintOut: NATIVE_MINT,
+        userIn: traderY.address,
+        userOut: null,
+        wsolTemp: wsolTemp(trader),
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([trader])
+      .rpc();
+
+    const solAfter = await provider.connection.getBalance(trader.publicKey);
+    expect(solAfter).to.be.greaterThan(solBefore);
+    expect(await provider.connection.getAccountInfo(wsolTemp(trader))).to.be.null;
+  });
+
+  it("Keeps an existing wSOL account when not opting in", async () => {
+    const traderWsol = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      trader,
+      NATIVE_MINT,
+      trader.publicKey
+    );
+    const traderY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      trader,
+      mintY,
+      trader.publicKey
+    );
+    await mintTo(provider.connection, authority, mintY, traderY.address, authority, 1_000_000);
+
+    // Y -> wSOL into the trader's own account
+    await program.methods
+      .swapV3(new anchor.BN(1_000_000), new anchor.BN(1))
+      .accounts({
+        user: trader.publicKey,
+        config: configPda,
+        mintIn: mintY,
+        mintOut: NATIVE_MINT,
+        userIn: traderY.address,
+        userOut: traderWsol.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([trader])
+      .rpc();
+
+    const wsolAfter = await getAccount(provider.connection, traderWsol.address);
+    expect(Number(wsolAfter.amount)).to.be.greaterThan(0);
+  });
+
+  it("Withdraws the native side as SOL", async () => {
+    const authY = getAssociatedTokenAddressSync(mintY, authority.publicKey);
+    const userLp = getAssociatedTokenAddressSync(lpMintPda, authority.publicKey);
+    const lpBalance = await getAccount(provider.connection, userLp);
+    const yBefore = await getAccount(provider.connection, authY);
+    const solBefore = await provider.connection.getBalance(authority.publicKey);
+
+    await program.methods
+      .withdraw(new anchor.BN(Math.floor(Number(lpBalance.amount) / 2)), new anchor.BN(1), new anchor.BN(1))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: null,
+        userY: authY,
+        userLp: userLp,
+        wsolTemp: wsolTemp(authority),
+        nativeMint: NATIVE_MINT,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const solAfter = await provider.connection.getBalance(authority.publicKey);
+    const yAfter = await getAccount(provider.connection, authY);
+    expect(solAfter).to.be.greaterThan(solBefore);
+    expect(Number(yAfter.amount)).to.be.greaterThan(Number(yBefore.amount));
+    expect(await provider.connection.getAccountInfo(wsolTemp(authority))).to.be.null;
+  });
+});
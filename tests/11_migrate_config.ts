@@ -0,0 +1,85 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+import fixtureV0 from "./fixtures/config_v0.json";
+import fixtureLocked from "./fixtures/config_v0_locked.json";
+
+describe("AMM Config Migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  // configs written by the pre-versioning program, loaded into the validator from Anchor.toml
+  const configV0 = new PublicKey(fixtureV0.pubkey);
+  const configLocked = new PublicKey(fixtureLocked.pubkey);
+
+  const migrate = (config: PublicKey) =>
+    program.methods
+      .migrateConfig()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        config,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  it("Loads the legacy fixtures", async () => {
+    for (const fixture of [fixtureV0, fixtureLocked]) {
+      const info = await provider.connection.getAccountInfo(new PublicKey(fixture.pubkey));
+      expect(info.data.length).to.equal(fixture.account.space);
+
+      // the current layout is larger, so legacy data no longer decodes as Config
+      expect(() => program.coder.accounts.decode("config", info.data)).to.throw();
+    }
+  });
+
+  it("Migrates a v0 config in place", async () => {
+    await migrate(configV0);
+
+    const info = await provider.connection.getAccountInfo(configV0);
+    expect(info.data.length).to.be.greaterThan(fixtureV0.account.space);
+
+    const configAccount = await program.account.config.fetch(configV0);
+    expect(configAccount.version).to.equal(1);
+    expect(configAccount.seed.toString()).to.equal("1000000042");
+    expect(configAccount.authority.toString()).to.equal(
+      "4652FtPxWwnTHVSgD3WJgLBHvPSFehaQEmpVEw4PQwmP"
+    );
+    expect(configAccount.mintX.toString()).to.equal("5rdYX4Np9YGLecMMgScGuz4Tz6j59FoCxSW7ABW8hr1o");
+    expect(configAccount.mintY.toString()).to.equal("A2Fut58E2Rgpnwi9pwqM4Wxevqsnq6ysvvrMp4coxD5K");
+    expect(configAccount.fee).to.equal(30);
+    expect(configAccount.paused).to.equal(0);
+    expect(configAccount.configBump).to.equal(255);
+    expect(configAccount.lpBump).to.equal(255);
+    expect(configAccount.reserved.every((byte: number) => byte === 0)).to.be.true;
+  });
+
+  it("Migrates a locked v0 config without an authority as fully paused", async () => {
+    await migrate(configLocked);
+
+    const configAccount = await program.account.config.fetch(configLocked);
+    expect(configAccount.version).to.equal(1);
+    expect(configAccount.seed.toString()).to.equal("1000000043");
+    expect(configAccount.authority.toString()).to.equal(PublicKey.default.toString());
+    expect(configAccount.mintX.toString()).to.equal("9uatgS2f4UoZVxWRUFpVzi1riTzjbqrbAFWzTekqbhc3");
+    expect(configAccount.mintY.toString()).to.equal("HRnfbeLtn4qkdWvuMwdf6DN4kRchnrtR1tAxjApdPJZr");
+    expect(configAccount.fee).to.equal(100);
+    expect(configAccount.paused).to.equal(0b1111);
+    expect(configAccount.withdrawPausedSlot.toNumber()).to.be.greaterThan(0);
+    expect(configAccount.dynamicFee.enabled).to.equal(0);
+    expect(configAccount.circuitBreaker.threshold).to.equal(0);
+    expect(configAccount.lpBump).to.equal(253);
+  });
+
+  it("Fails to migrate an already migrated config", async () => {
+    try {
+      await migrate(configV0);
+
+      expect.fail("Should have failed with InvalidConfigLayout");
+    } catch (error: any) {
+      expect(error.message).to.include("InvalidConfigLayout");
+    }
+  });
+});
@@ -0,0 +1,401 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+describe("AMM LP Farming", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  const rewardRate = new anchor.BN(1_000); // reward tokens per second
+  const funding = new anchor.BN(10_000_000);
+
+  let authority: Keypair;
+  let user: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let rewardMint: PublicKey;
+  let farmPda: PublicKey;
+  let rewardVaultPda: PublicKey;
+  let lpVaultPda: PublicKey;
+  let positionPda: PublicKey;
+  let userLp: PublicKey;
+  let userReward: PublicKey;
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    user = Keypair.generate();
+
+    const seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(user.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    const mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    rewardMint = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [farmPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm"), configPda.toBuffer(), rewardMint.toBuffer()],
+      program.programId
+    );
+    [rewardVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_reward_vault"), farmPda.toBuffer()],
+      program.programId
+    );
+    [lpVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("farm_lp_vault"), farmPda.toBuffer()],
+      program.programId
+    );
+    [positionPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("stake"), farmPda.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, 30)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // give the user LP tokens to stake
+    const userX = await getOrCreateAssociatedTokenAccount(provider.connection, user, mintX, user.publicKey);
+    const userY = await getOrCreateAssociatedTokenAccount(provider.connection, user, mintY, user.publicKey);
+    await mintTo(provider.connection, authority, mintX, userX.address, authority, 1_000_000_000);
+    await mintTo(provider.connection, authority, mintY, userY.address, authority, 1_000_000_000);
+    userLp = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, user, lpMintPda, user.publicKey)
+    ).address;
+    userReward = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, user, rewardMint, user.publicKey)
+    ).address;
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(100_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userX.address,
+        userY: userY.address,
+        userLp: userLp,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    // the pool authority starts the farm and also acts as reward authority
+    await program.methods
+      .initializeFarm(authority.publicKey, rewardRate)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        rewardMint: rewardMint,
+        farm: farmPda,
+        rewardVault: rewardVaultPda,
+        lpVault: lpVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const funder = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      rewardMint,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority, rewardMint, funder.address, authority, funding.toNumber());
+
+    await program.methods
+      .fundFarm(funding)
+      .accounts({
+        rewardAuthority: authority.publicKey,
+        farm: farmPda,
+        funder: funder.address,
+        rewardVault: rewardVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([authority])
+      .rpc();
+  });
+
+  const stakeAccounts = () => ({
+    user: user.publicKey,
+    farm: farmPda,
+    position: positionPda,
+    userLp: userLp,
+    lpVault: lpVaultPda,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const stakeLp = (amount: number) =>
+    program.methods
+      .stakeLp(new anchor.BN(amount))
+      .accounts({ ...stakeAccounts(), systemProgram: SystemProgram.programId })
+      .signers([user])
+      .rpc();
+
+  const claimRewards = () =>
+    program.methods
+      .claimRewards()
+      .accounts({
+        user: user.publicKey,
+        farm: farmPda,
+        position: positionPda,
+        userReward: userReward,
+        rewardVault: rewardVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+  const claimed = async () =>
+    new anchor.BN((await getAccount(provider.connection, userReward)).amount.toString());
+
+  // REWARD_PRECISION, the scale of the reward-per-share accumulator
+  const precision = new anchor.BN("1000000000000");
+
+  // resolves once the cluster clock the program reads reaches `ts`
+  const waitForClock = async (ts: number) => {
+    for (;;) {
+      const clock = await provider.connection.getAccountInfo(SYSVAR_CLOCK_PUBKEY);
+      if (Number(clock.data.readBigInt64LE(32)) >= ts) {
+        return;
+      }
+      await new Promise<void>((resolve) => {
+        const id = provider.connection.onSlotChange(() => {
+          provider.connection.removeSlotChangeListener(id);
+          resolve();
+        });
+      });
+    }
+  };
+
+  it("Stakes LP tokens, earns rewards and unstakes", async () => {
+    const lpBefore = await getAccount(provider.connection, userLp);
+    const stakeAmount = new anchor.BN(lpBefore.amount.toString());
+
+    await program.methods
+      .stakeLp(stakeAmount)
+      .accounts({ ...stakeAccounts(), systemProgram: SystemProgram.programId })
+      .signers([user])
+      .rpc();
+
+    const farm = await program.account.farm.fetch(farmPda);
+    expect(farm.totalStaked.toString()).to.equal(stakeAmount.toString());
+
+    // let a few seconds of emissions accrue
+    await waitForClock(farm.lastUpdateTs.toNumber() + 3);
+
+    await program.methods
+      .claimRewards()
+      .accounts({
+        user: user.publicKey,
+        farm: farmPda,
+        position: positionPda,
+        userReward: userReward,
+        rewardVault: rewardVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+    const rewards = await getAccount(provider.connection, userReward);
+    expect(Number(rewards.amount)).to.be.greaterThan(0);
+    expect(Number(rewards.amount)).to.be.at.most(funding.toNumber());
+
+    await program.methods
+      .unstakeLp(stakeAmount)
+      .accounts(stakeAccounts())
+      .signers([user])
+      .rpc();
+
+    const lpAfter = await getAccount(provider.connection, userLp);
+    expect(lpAfter.amount).to.equal(lpBefore.amount);
+
+    const farmAfter = await program.account.farm.fetch(farmPda);
+    expect(farmAfter.totalStaked.toNumber()).to.equal(0);
+  });
+
+  it("Fails to unstake more than the staked balance", async () => {
+    await program.methods
+      .stakeLp(new anchor.BN(1_000))
+      .accounts({ ...stakeAccounts(), systemProgram: SystemProgram.programId })
+      .signers([user])
+      .rpc();
+
+    try {
+      await program.methods
+        .unstakeLp(new anchor.BN(1_001))
+        .accounts(stakeAccounts())
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with InsufficientStake");
+    } catch (error: any) {
+      expect(error.message).to.include("InsufficientStake");
+    }
+  });
+
+  it("Rejects rate changes from anyone but the reward authority", async () => {
+    try {
+      await program.methods
+        .setRewardRate(new anchor.BN(1_000_000))
+        .accounts({
+          rewardAuthority: user.publicKey,
+          farm: farmPda,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with Unauthorized");
+    } catch (error: any) {
+      expect(error.message).to.include("Unauthorized");
+    }
+  });
+
+  it("Rounds the reward per share down and keeps the remainder owed", async () => {
+    await stakeLp(3);
+    const before = await program.account.farm.fetch(farmPda);
+
+    await waitForClock(before.lastUpdateTs.toNumber() + 2);
+    await claimRewards();
+
+    const after = await program.account.farm.fetch(farmPda);
+    const elapsed = after.lastUpdateTs.sub(before.lastUpdateTs);
+    const emitted = anchor.BN.min(elapsed.mul(rewardRate), before.undistributed);
+
+    // the emission over 3 shares is rounded down
+    expect(after.accRewardPerShare.sub(before.accRewardPerShare).toString()).to.equal(
+      emitted.mul(precision).divn(3).toString()
+    );
+    expect(after.undistributed.toString()).to.equal(before.undistributed.sub(emitted).toString());
+
+    // the claim pays whole tokens, the rounded-off part stays owed in the debt
+    const earned = after.accRewardPerShare.muln(3).div(precision);
+    expect((await claimed()).toString()).to.equal(earned.toString());
+
+    const position = await program.account.stakePosition.fetch(positionPda);
+    expect(position.rewardDebt.toString()).to.equal(earned.mul(precision).toString());
+  });
+
+  it("Caps emissions at the funded rewards", async () => {
+    await stakeLp(1_000);
+
+    // the whole funding is due within a second
+    await program.methods
+      .setRewardRate(funding)
+      .accounts({
+        rewardAuthority: authority.publicKey,
+        farm: farmPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    const farm = await program.account.farm.fetch(farmPda);
+    await waitForClock(farm.lastUpdateTs.toNumber() + 2);
+    await claimRewards();
+
+    const after = await program.account.farm.fetch(farmPda);
+    expect(after.undistributed.toNumber()).to.equal(0);
+    expect((await claimed()).toString()).to.equal(funding.toString());
+
+    // nothing is left to emit
+    await waitForClock(after.lastUpdateTs.toNumber() + 1);
+    try {
+      await claimRewards();
+      expect.fail("Should have failed with NoRewards");
+    } catch (error: any) {
+      expect(error.message).to.include("NoRewards");
+    }
+  });
+
+  it("Carries the owed remainder over a stake change", async () => {
+    await stakeLp(3);
+    const opened = await program.account.farm.fetch(farmPda);
+    await waitForClock(opened.lastUpdateTs.toNumber() + 2);
+
+    // staking more settles the old stake first
+    await stakeLp(2);
+    const restaked = await program.account.farm.fetch(farmPda);
+    const accrued = restaked.accRewardPerShare.muln(3);
+    const settled = accrued.div(precision);
+    const remainder = accrued.sub(settled.mul(precision));
+
+    await waitForClock(restaked.lastUpdateTs.toNumber() + 2);
+    await claimRewards();
+
+    // the remainder of the old stake counts towards the new one
+    const after = await program.account.farm.fetch(farmPda);
+    const earned = after.accRewardPerShare
+      .sub(restaked.accRewardPerShare)
+      .muln(5)
+      .add(remainder)
+      .div(precision);
+    expect((await claimed()).toString()).to.equal(settled.add(earned).toString());
+  });
+});
@@ -0,0 +1,265 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, SYSVAR_CLOCK_PUBKEY } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+describe("AMM LP Locks", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let authority: Keypair;
+  let user: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let lockPda: PublicKey;
+  let lockVaultPda: PublicKey;
+  let userLp: PublicKey;
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    user = Keypair.generate();
+
+    const seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(user.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    const mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, 30)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // give the user LP tokens to lock
+    const userX = await getOrCreateAssociatedTokenAccount(provider.connection, user, mintX, user.publicKey);
+    const userY = await getOrCreateAssociatedTokenAccount(provider.connection, user, mintY, user.publicKey);
+    await mintTo(provider.connection, authority, mintX, userX.address, authority, 1_000_000_000);
+    await mintTo(provider.connection, authority, mintY, userY.address, authority, 1_000_000_000);
+    userLp = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, user, lpMintPda, user.publicKey)
+    ).address;
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(100_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userX.address,
+        userY: userY.address,
+        userLp: userLp,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    [lockPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_lock"), configPda.toBuffer(), user.publicKey.toBuffer()],
+      program.programId
+    );
+    [lockVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_lock_vault"), lockPda.toBuffer()],
+      program.programId
+    );
+  });
+
+  // unix timestamp of the cluster clock the program reads
+  const now = async () => {
+    const clock = await provider.connection.getAccountInfo(SYSVAR_CLOCK_PUBKEY);
+    return Number(clock.data.readBigInt64LE(32));
+  };
+
+  // resolves once the cluster clock reaches `ts`
+  const waitForClock = async (ts: number) => {
+    while ((await now()) < ts) {
+      await new Promise<void>((resolve) => {
+        const id = provider.connection.onSlotChange(() => {
+          provider.connection.removeSlotChangeListener(id);
+          resolve();
+        });
+      });
+    }
+  };
+
+  const lockLp = (amount: anchor.BN, unlockTs: number, linear: boolean) =>
+    program.methods
+      .lockLp(amount, new anchor.BN(unlockTs), linear)
+      .accounts({
+        owner: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        ownerLp: userLp,
+        lock: lockPda,
+        lockVault: lockVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+  const unlockLp = () =>
+    program.methods
+      .unlockLp()
+      .accounts({
+        owner: user.publicKey,
+        lock: lockPda,
+        lockVault: lockVaultPda,
+        ownerLp: userLp,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+  it("Releases a cliff lock only after the unlock time", async () => {
+    const lpBefore = await getAccount(provider.connection, userLp);
+    const amount = new anchor.BN(lpBefore.amount.toString());
+
+    await lockLp(amount, (await now()) + 3, false);
+
+    const lock = await program.account.lpLock.fetch(lockPda);
+    expect(lock.amount.toString()).to.equal(amount.toString());
+    expect(Number((await getAccount(provider.connection, userLp)).amount)).to.equal(0);
+
+    try {
+      await unlockLp();
+      expect.fail("Should have failed with NothingUnlocked");
+    } catch (error: any) {
+      expect(error.message).to.include("NothingUnlocked");
+    }
+
+    await waitForClock(lock.unlockTs.toNumber());
+    await unlockLp();
+
+    const lpAfter = await getAccount(provider.connection, userLp);
+    expect(lpAfter.amount).to.equal(lpBefore.amount);
+
+    // the spent lock is closed so the owner can lock again
+    expect(await provider.connection.getAccountInfo(lockPda)).to.be.null;
+  });
+
+  it("Vests a linear lock over time", async () => {
+    const lpBefore = await getAccount(provider.connection, userLp);
+    const amount = new anchor.BN(lpBefore.amount.toString());
+
+    await lockLp(amount, (await now()) + 60, true);
+    const created = await program.account.lpLock.fetch(lockPda);
+    await waitForClock(created.startTs.toNumber() + 3);
+    await unlockLp();
+
+    const lock = await program.account.lpLock.fetch(lockPda);
+    expect(lock.released.toNumber()).to.be.greaterThan(0);
+    expect(lock.released.lt(amount)).to.be.true;
+
+    const lpAfter = await getAccount(provider.connection, userLp);
+    expect(lpAfter.amount.toString()).to.equal(lock.released.toString());
+  });
+
+  it("Rounds linear vesting down and releases the rest at the unlock time", async () => {
+    const lpBefore = await getAccount(provider.connection, userLp);
+    const amount = new anchor.BN(999);
+
+    await lockLp(amount, (await now()) + 4, true);
+    const created = await program.account.lpLock.fetch(lockPda);
+    const start = created.startTs.toNumber();
+    const unlockTs = created.unlockTs.toNumber();
+
+    await waitForClock(start + 2);
+    const sig = await unlockLp();
+
+    // the program vests against the clock of the block the unlock landed in
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const elapsed = tx.blockTime - start;
+    const expected =
+      tx.blockTime >= unlockTs
+        ? amount
+        : amount.muln(elapsed).divn(unlockTs - start);
+
+    const lock = await program.account.lpLock.fetch(lockPda);
+    expect(lock.released.toString()).to.equal(expected.toString());
+
+    // whatever was rounded off is released in full at the unlock time
+    await waitForClock(unlockTs);
+    await unlockLp();
+
+    const lpAfter = await getAccount(provider.connection, userLp);
+    expect(lpAfter.amount).to.equal(lpBefore.amount);
+    expect(await provider.connection.getAccountInfo(lockPda)).to.be.null;
+  });
+
+  it("Fails to lock with an unlock time in the past", async () => {
+    try {
+      await lockLp(new anchor.BN(1_000), (await now()) - 60, false);
+      expect.fail("Should have failed with InvalidUnlockTime");
+    } catch (error: any) {
+      expect(error.message).to.include("InvalidUnlockTime");
+    }
+  });
+});
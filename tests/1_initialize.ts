@@ -2,7 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Amm } from "../target/types/amm";
 import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint} from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getMint } from "@solana/spl-token";
 import { expect } from "chai";
 
 describe("AMM Pool Initialization", () => {
@@ -10,6 +10,12 @@ describe("AMM Pool Initialization", () => {
   anchor.setProvider(provider);
   const program = anchor.workspace.amm as Program<Amm>;
 
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
   let mintX: PublicKey;
   let mintY: PublicKey;
   let authority: Keypair;
@@ -64,17 +70,26 @@ describe("AMM Pool Initialization", () => {
       program.programId,
     );
 
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
     // Initialize the pool
     const tx = await program.methods
       .initialize(seed, fee)
       .accounts({
         authority: authority.publicKey,
+        factory: factoryPda,
         config: configPda,
         mintX: mintX,
         mintY: mintY,
         lpMint: lpMintPda,
         vaultX: vaultXPda,
         vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -90,7 +105,16 @@ describe("AMM Pool Initialization", () => {
     expect(configAccount.fee).to.equal(fee);
     expect(configAccount.mintX.toString()).to.equal(mintX.toString());
     expect(configAccount.mintY.toString()).to.equal(mintY.toString());
-    expect(configAccount.locked).to.be.false;
+    expect(configAccount.paused).to.equal(0);
+    expect(configAccount.version).to.equal(1);
+
+    // Verify the pool was registered in the factory
+    const poolIndexAccount = await program.account.poolIndex.fetch(poolIndexPda);
+    expect(poolIndexAccount.config.toString()).to.equal(configPda.toString());
+    expect(poolIndexAccount.index.toString()).to.equal(factoryAccount.poolCount.toString());
+
+    const factoryAfter = await program.account.factory.fetch(factoryPda);
+    expect(factoryAfter.poolCount.toNumber()).to.equal(factoryAccount.poolCount.toNumber() + 1);
   });
 
   it("Fails with invalid fee", async () => {
@@ -118,17 +142,26 @@ describe("AMM Pool Initialization", () => {
       program.programId,
     );
 
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
     try {
       await program.methods
         .initialize(seed, fee)
         .accounts({
           authority: authority.publicKey,
+          factory: factoryPda,
           config: configPda,
           mintX: mintX,
           mintY: mintY,
           lpMint: lpMintPda,
           vaultX: vaultXPda,
           vaultY: vaultYPda,
+          poolIndex: poolIndexPda,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -142,6 +175,64 @@ describe("AMM Pool Initialization", () => {
     }
   });
 
+  it("Fails with non-whitelisted fee tier", async () => {
+    const seed = new anchor.BN(12350);
+    const fee = 50; // within max fee but not a whitelisted tier
+
+    // Derive PDA addresses
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    try {
+      await program.methods
+        .initialize(seed, fee)
+        .accounts({
+          authority: authority.publicKey,
+          factory: factoryPda,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          poolIndex: poolIndexPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+      
+        expect.fail("Should have failed with non-whitelisted fee.");
+    } catch (error) {
+      expect(error.message).to.include("FeeTierNotAllowed");
+    }
+  });
+
   it("Fails when mint X equals mint Y", async () => {
     const seed = new anchor.BN(12347);
     const fee = 300;
@@ -167,17 +258,26 @@ describe("AMM Pool Initialization", () => {
       program.programId,
     );
 
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
     try {
       await program.methods
         .initialize(seed, fee)
         .accounts({
           authority: authority.publicKey,
+          factory: factoryPda,
           config: configPda,
           mintX: mintX,
           mintY: mintX, // Same mint - should fail
           lpMint: lpMintPda,
           vaultX: vaultXPda,
           vaultY: vaultYPda,
+          poolIndex: poolIndexPda,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -190,4 +290,251 @@ describe("AMM Pool Initialization", () => {
       expect(error.message).to.include("InvalidToken");
     }
   })
+
+  it("Initializes a canonical pool with sorted mints", async () => {
+    const seed = new anchor.BN(12348);
+    const fee = 30;
+
+    // canonical pools require mint_x < mint_y
+    const [sortedX, sortedY] =
+      Buffer.compare(mintX.toBuffer(), mintY.toBuffer()) < 0 ? [mintX, mintY] : [mintY, mintX];
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [canonicalPoolPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        sortedX.toBuffer(),
+        sortedY.toBuffer(),
+        new anchor.BN(fee).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId,
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: sortedX,
+        mintY: sortedY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        canonicalPool: canonicalPoolPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const canonicalPool = await program.account.canonicalPool.fetch(canonicalPoolPda);
+    expect(canonicalPool.config.toString()).to.equal(configPda.toString());
+    expect(canonicalPool.seed.toString()).to.equal(seed.toString());
+
+    const configAccount = await program.account.config.fetch(configPda);
+    expect(configAccount.canonical).to.equal(1);
+  });
+
+  it("Fails canonical pool with unsorted mints", async () => {
+    const seed = new anchor.BN(12349);
+    const fee = 30;
+
+    const [unsortedX, unsortedY] =
+      Buffer.compare(mintX.toBuffer(), mintY.toBuffer()) < 0 ? [mintY, mintX] : [mintX, mintY];
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [canonicalPoolPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("pool"),
+        unsortedX.toBuffer(),
+        unsortedY.toBuffer(),
+        new anchor.BN(fee).toArrayLike(Buffer, "le", 2),
+      ],
+      program.programId,
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    try {
+      await program.methods
+        .initialize(seed, fee)
+        .accounts({
+          authority: authority.publicKey,
+          factory: factoryPda,
+          config: configPda,
+          mintX: unsortedX,
+          mintY: unsortedY,
+          lpMint: lpMintPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          canonicalPool: canonicalPoolPda,
+          poolIndex: poolIndexPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      expect.fail("Should have failed with unsorted mints.");
+    } catch (error) {
+      expect(error.message).to.include("InvalidMintOrder");
+    }
+  });
+
+  it("Derives LP decimals from the pair and names the LP token", async () => {
+    const seed = new anchor.BN(12351);
+    const fee = 30;
+
+    // 6 and 9 decimals average to 7 for sqrt(x * y)
+    const mintY9 = await createMint(
+      provider.connection,
+      authority,
+      authority.publicKey,
+      null,
+      9,
+    );
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY9,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const lpMint = await getMint(provider.connection, lpMintPda);
+    expect(lpMint.decimals).to.equal(7);
+
+    const [metadataPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), lpMintPda.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    );
+
+    // neither test mint has metadata, so the name falls back to their addresses
+    const [metadataXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mintX.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    );
+    const [metadataYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mintY9.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID,
+    );
+    const metadataAccounts = {
+      config: configPda,
+      lpMint: lpMintPda,
+      metadataX: metadataXPda,
+      metadataY: metadataYPda,
+      metadata: metadataPda,
+      tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+    };
+
+    // only the pool authority can name the LP token of a pool that has one
+    const stranger = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(stranger.publicKey, 1_000_000_000)
+    );
+    try {
+      await program.methods
+        .createLpMetadata("")
+        .accounts({ payer: stranger.publicKey, ...metadataAccounts })
+        .signers([stranger])
+        .rpc();
+
+      expect.fail("Should have failed with unauthorized payer.");
+    } catch (error) {
+      expect(error.message).to.include("Unauthorized");
+    }
+
+    await program.methods
+      .createLpMetadata("")
+      .accounts({ payer: authority.publicKey, ...metadataAccounts })
+      .signers([authority])
+      .rpc();
+
+    const name = `AMM LP ${mintX.toBase58().slice(0, 4)}-${mintY9.toBase58().slice(0, 4)}`;
+    const metadata = await provider.connection.getAccountInfo(metadataPda);
+    expect(metadata.owner.toString()).to.equal(TOKEN_METADATA_PROGRAM_ID.toString());
+    expect(metadata.data.toString()).to.include(name);
+  });
 });
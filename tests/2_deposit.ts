@@ -17,6 +17,11 @@ describe("AMM Deposit", () => {
   anchor.setProvider(provider);
   const program = anchor.workspace.Amm as Program<Amm>;
 
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
   let mintX: PublicKey;
   let mintY: PublicKey;
   let authority: Keypair;
@@ -67,17 +72,26 @@ describe("AMM Deposit", () => {
       program.programId
     );
 
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
     // initialize pool
     await program.methods
       .initialize(seed, fee)
       .accounts({
         authority: authority.publicKey,
+        factory: factoryPda,
         config: configPda,
         mintX: mintX,
         mintY: mintY,
         lpMint: lpMintPda,
         vaultX: vaultXPda,
         vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -142,12 +156,10 @@ describe("AMM Deposit", () => {
     );
 
     const tx = await program.methods
-      .deposit(amountX, amountY, minLp)
+      .deposit(amountX, amountY, minLp, new anchor.BN(0), new anchor.BN(0))
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -197,12 +209,10 @@ describe("AMM Deposit", () => {
     );
 
     await program.methods
-      .deposit(amountX1, amountY1, minLp1)
+      .deposit(amountX1, amountY1, minLp1, new anchor.BN(0), new anchor.BN(0))
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -223,12 +233,10 @@ describe("AMM Deposit", () => {
     const balanceBefore = await getAccount(provider.connection, userLpAccount.address);
 
     await program.methods
-      .deposit(amountX2, amountY2, minLp2)
+      .deposit(amountX2, amountY2, minLp2, new anchor.BN(0), new anchor.BN(0))
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -247,6 +255,133 @@ describe("AMM Deposit", () => {
     expect(lpReceived).to.be.greaterThan(0);
   });
 
+  it("Only transfers tokens matching the pool ratio", async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+
+    // first deposit sets a 1:2 price
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userXAccount.address,
+        userY: userYAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    const vaultXBefore = await getAccount(provider.connection, vaultXPda);
+    const vaultYBefore = await getAccount(provider.connection, vaultYPda);
+
+    // offer far more Y than the ratio needs
+    await program.methods
+      .deposit(new anchor.BN(50_000_000), new anchor.BN(500_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userXAccount.address,
+        userY: userYAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    const vaultXAfter = await getAccount(provider.connection, vaultXPda);
+    const vaultYAfter = await getAccount(provider.connection, vaultYPda);
+
+    const depositedX = Number(vaultXAfter.amount) - Number(vaultXBefore.amount);
+    const depositedY = Number(vaultYAfter.amount) - Number(vaultYBefore.amount);
+
+    // X is the limiting side, Y is taken at the pool ratio (rounded up by at most 1)
+    expect(depositedX).to.be.at.most(50_000_000);
+    expect(depositedY).to.be.at.least(depositedX * 2);
+    expect(depositedY).to.be.at.most(depositedX * 2 + 2);
+  });
+
+  it("Fails deposit when the ratio leaves less than the minimum of one side", async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+    const depositAccounts = {
+      user: user.publicKey,
+      config: configPda,
+      lpMint: lpMintPda,
+      userX: userXAccount.address,
+      userY: userYAccount.address,
+      userLp: userLpAccount.address,
+      vaultX: vaultXPda,
+      vaultY: vaultYPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // first deposit sets a 1:2 price
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts(depositAccounts)
+      .signers([user])
+      .rpc();
+
+    try {
+      // 50 X only matches ~100 Y, below the 200 Y minimum
+      await program.methods
+        .deposit(new anchor.BN(50_000_000), new anchor.BN(500_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(200_000_000))
+        .accounts(depositAccounts)
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with slippage exceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+  });
+
   it("Fails deposit with insufficient balance", async () => {
     const amountX = new anchor.BN(2_000_000_000); // more than minted
     const amountY = new anchor.BN(200_000_000);
@@ -273,12 +408,10 @@ describe("AMM Deposit", () => {
 
     try {
       await program.methods
-        .deposit(amountX, amountY, minLp)
+        .deposit(amountX, amountY, minLp, new anchor.BN(0), new anchor.BN(0))
         .accounts({
           user: user.publicKey,
           config: configPda,
-          mintX: mintX,
-          mintY: mintY,
           lpMint: lpMintPda,
           userX: userXAccount.address,
           userY: userYAccount.address,
@@ -17,6 +17,11 @@ describe("AMM Withdraw", () => {
   anchor.setProvider(provider);
   const program = anchor.workspace.Amm as Program<Amm>;
 
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
   let mintX: PublicKey;
   let mintY: PublicKey;
   let authority: Keypair;
@@ -25,6 +30,7 @@ describe("AMM Withdraw", () => {
   let lpMintPda: PublicKey;
   let vaultXPda: PublicKey;
   let vaultYPda: PublicKey;
+  let poolIndexPda: PublicKey;
   let seed: anchor.BN;
   const fee = 300; // 3%
 
@@ -63,16 +69,25 @@ describe("AMM Withdraw", () => {
       program.programId
     );
 
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
     await program.methods
       .initialize(seed, fee)
       .accounts({
         authority: authority.publicKey,
+        factory: factoryPda,
         config: configPda,
         mintX: mintX,
         mintY: mintY,
         lpMint: lpMintPda,
         vaultX: vaultXPda,
         vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -136,12 +151,10 @@ describe("AMM Withdraw", () => {
     );
 
     await program.methods
-      .deposit(amountX, amountY, minLp)
+      .deposit(amountX, amountY, minLp, new anchor.BN(0), new anchor.BN(0))
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -166,8 +179,6 @@ describe("AMM Withdraw", () => {
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -211,12 +222,10 @@ describe("AMM Withdraw", () => {
     );
 
     await program.methods
-      .deposit(amountX, amountY, minLp)
+      .deposit(amountX, amountY, minLp, new anchor.BN(0), new anchor.BN(0))
       .accounts({
         user: user.publicKey,
         config: configPda,
-        mintX: mintX,
-        mintY: mintY,
         lpMint: lpMintPda,
         userX: userXAccount.address,
         userY: userYAccount.address,
@@ -243,8 +252,6 @@ describe("AMM Withdraw", () => {
         .accounts({
           user: user.publicKey,
           config: configPda,
-          mintX: mintX,
-          mintY: mintY,
           lpMint: lpMintPda,
           userX: userXAccount.address,
           userY: userYAccount.address,
@@ -261,6 +268,295 @@ describe("AMM Withdraw", () => {
       expect(error.message).to.include("SlippageExceeded");
     }
   });
+
+  it("Withdraws while deposits are paused", async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+
+    const depositAccounts = {
+      user: user.publicKey,
+      config: configPda,
+      lpMint: lpMintPda,
+      userX: userXAccount.address,
+      userY: userYAccount.address,
+      userLp: userLpAccount.address,
+      vaultX: vaultXPda,
+      vaultY: vaultYPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts(depositAccounts)
+      .signers([user])
+      .rpc();
+
+    // pause deposits and swaps
+    await program.methods
+      .setPause(1 | 4)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    try {
+      await program.methods
+        .deposit(new anchor.BN(1_000_000), new anchor.BN(2_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+        .accounts(depositAccounts)
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with DepositsPaused");
+    } catch (error: any) {
+      expect(error.message).to.include("DepositsPaused");
+    }
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+
+    await program.methods
+      .withdraw(new anchor.BN(Number(lpBalance.amount)), new anchor.BN(1), new anchor.BN(1))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userXAccount.address,
+        userY: userYAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+    const lpAfter = await getAccount(provider.connection, userLpAccount.address);
+    expect(Number(lpAfter.amount)).to.equal(0);
+  });
+
+  it("Rejects emergency withdrawal before the grace period", async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userXAccount.address,
+        userY: userYAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    // pause withdrawals
+    await program.methods
+      .setPause(2)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    let configAccount = await program.account.config.fetch(configPda);
+    const pausedSlot = configAccount.withdrawPausedSlot.toNumber();
+    expect(pausedSlot).to.be.greaterThan(0);
+
+    // a quick unpause and re-pause keeps the original pause slot
+    for (const paused of [0, 2]) {
+      await program.methods
+        .setPause(paused)
+        .accounts({
+          authority: authority.publicKey,
+          config: configPda,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    configAccount = await program.account.config.fetch(configPda);
+    expect(configAccount.withdrawResumedSlot.toNumber()).to.be.at.least(pausedSlot);
+    expect(configAccount.withdrawPausedSlot.toNumber()).to.equal(pausedSlot);
+
+    try {
+      await program.methods
+        .emergencyWithdraw(new anchor.BN(1_000))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userX: userXAccount.address,
+          userY: userYAccount.address,
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with EmergencyWithdrawUnavailable");
+    } catch (error: any) {
+      expect(error.message).to.include("EmergencyWithdrawUnavailable");
+    }
+  });
+
+  it("Closes an empty pool and reclaims its rent", async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+    const authorityXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintX,
+      authority.publicKey
+    );
+    const authorityYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+
+    const poolAccounts = {
+      user: user.publicKey,
+      config: configPda,
+      lpMint: lpMintPda,
+      userX: userXAccount.address,
+      userY: userYAccount.address,
+      userLp: userLpAccount.address,
+      vaultX: vaultXPda,
+      vaultY: vaultYPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const closeAccounts = {
+      authority: authority.publicKey,
+      config: configPda,
+      lpMint: lpMintPda,
+      vaultX: vaultXPda,
+      vaultY: vaultYPda,
+      authorityX: authorityXAccount.address,
+      authorityY: authorityYAccount.address,
+      canonicalPool: null,
+      poolIndex: poolIndexPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        ...poolAccounts,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    // outstanding LP tokens block the closure
+    try {
+      await program.methods
+        .closePool()
+        .accounts(closeAccounts)
+        .signers([authority])
+        .rpc();
+
+      expect.fail("Should have failed with PoolNotEmpty");
+    } catch (error: any) {
+      expect(error.message).to.include("PoolNotEmpty");
+    }
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    await program.methods
+      .withdraw(new anchor.BN(lpBalance.amount.toString()), new anchor.BN(1), new anchor.BN(1))
+      .accounts(poolAccounts)
+      .signers([user])
+      .rpc();
+
+    // the locked minimum liquidity stays in the vaults and is swept to the authority
+    const vaultX = await getAccount(provider.connection, vaultXPda);
+    const vaultY = await getAccount(provider.connection, vaultYPda);
+
+    await program.methods
+      .closePool()
+      .accounts(closeAccounts)
+      .signers([authority])
+      .rpc();
+
+    for (const closed of [configPda, vaultXPda, vaultYPda]) {
+      expect(await provider.connection.getAccountInfo(closed)).to.be.null;
+    }
+
+    // the registry entry stays as a tombstone so pools can still be listed by index
+    const poolIndexAccount = await program.account.poolIndex.fetch(poolIndexPda);
+    expect(poolIndexAccount.config.toString()).to.equal(PublicKey.default.toString());
+
+    const authorityX = await getAccount(provider.connection, authorityXAccount.address);
+    const authorityY = await getAccount(provider.connection, authorityYAccount.address);
+    expect(authorityX.amount).to.equal(vaultX.amount);
+    expect(authorityY.amount).to.equal(vaultY.amount);
+  });
 });
 
 
@@ -5,9 +5,11 @@ import {
     TOKEN_PROGRAM_ID,
     ASSOCIATED_TOKEN_PROGRAM_ID,
     createMint,
+    createAccount,
     mintTo,
     getOrCreateAssociatedTokenAccount,
-    getAccount
+    getAccount,
+    getAssociatedTokenAddressSync,
 } from "@solana/spl-token";
 import { Amm } from "../target/types/amm";
 import { expect } from "chai"; 
@@ -17,6 +19,11 @@ describe("AMM Token Swapping", () => {
     anchor.setProvider(provider);
     const program = anchor.workspace.Amm as Program<Amm>;
 
+    const [factoryPda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("factory")],
+        program.programId,
+    );
+
     let mintX: PublicKey;
     let mintY: PublicKey;
     let authority: Keypair;
@@ -89,17 +96,26 @@ describe("AMM Token Swapping", () => {
             program.programId,
         );
 
+        // next registry slot in the factory
+        const factoryAccount = await program.account.factory.fetch(factoryPda);
+        const [poolIndexPda] = PublicKey.findProgramAddressSync(
+            [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+            program.programId,
+        );
+
         // Initialize the pool
         await program.methods
             .initialize(seed, fee)
             .accounts({
                 authority: authority.publicKey,
+                factory: factoryPda,
                 config: configPda,
                 mintX: mintX,
                 mintY: mintY,
                 lpMint: lpMintPda,
                 vaultX: vaultXPda,
                 vaultY: vaultYPda,
+                poolIndex: poolIndexPda,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
@@ -149,12 +165,10 @@ describe("AMM Token Swapping", () => {
         );
 
         await program.methods
-            .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1))
+            .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
             .accounts({
                 user: authority.publicKey,
                 config: configPda,
-                mintX: mintX,
-                mintY: mintY,
                 lpMint: lpMintPda,
                 userX: authX.address,
                 userY: authY.address,
@@ -229,7 +243,7 @@ describe("AMM Token Swapping", () => {
                 userY: traderY.address,
                 vaultX: vaultXPda,
                 vaultY: vaultYPda,
-                tokenProgram: TOKEN_PROGRAM_ID
+                tokenProgram: TOKEN_PROGRAM_ID,
             })
             .signers([trader])
             .rpc();
@@ -282,6 +296,194 @@ describe("AMM Token Swapping", () => {
         expect(Number(outAfter.amount)).to.be.greaterThan(0);
     });
 
+    it("Sends the output to a separate recipient", async () => {
+        const recipient = Keypair.generate();
+        const traderX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintX,
+            trader.publicKey
+        );
+        const traderY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            trader.publicKey,
+        );
+        const recipientY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            recipient.publicKey,
+        );
+
+        const traderYBefore = await getAccount(provider.connection, traderY.address);
+
+        await program.methods
+            .swap(true, new anchor.BN(1_000_000), new anchor.BN(1))
+            .accounts({
+                user: trader.publicKey,
+                config: configPda,
+                mintX: mintX,
+                mintY: mintY,
+                userX: traderX.address,
+                userY: traderY.address,
+                vaultX: vaultXPda,
+                vaultY: vaultYPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                recipient: recipientY.address,
+            })
+            .signers([trader])
+            .rpc();
+
+        const recipientAfter = await getAccount(provider.connection, recipientY.address);
+        const traderYAfter = await getAccount(provider.connection, traderY.address);
+        expect(Number(recipientAfter.amount)).to.be.greaterThan(0);
+        expect(traderYAfter.amount).to.equal(traderYBefore.amount);
+    });
+
+    it("Swaps through swap_v3 from a non-associated input account to a recipient", async () => {
+        const holder = Keypair.generate();
+        await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(holder.publicKey, 1_000_000_000)
+        );
+
+        const holderX = await createAccount(
+            provider.connection,
+            holder,
+            mintX,
+            holder.publicKey,
+            Keypair.generate(),
+        );
+        await mintTo(provider.connection, authority, mintX, holderX, authority, 1_000_000);
+        const traderY = getAssociatedTokenAddressSync(mintY, trader.publicKey);
+        const traderYBefore = await getAccount(provider.connection, traderY);
+
+        await program.methods
+            .swapV3(new anchor.BN(1_000_000), new anchor.BN(1))
+            .accounts({
+                user: holder.publicKey,
+                config: configPda,
+                mintIn: mintX,
+                mintOut: mintY,
+                userIn: holderX,
+                userOut: null,
+                recipient: traderY,
+                vaultX: vaultXPda,
+                vaultY: vaultYPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([holder])
+            .rpc();
+
+        const holderXAfter = await getAccount(provider.connection, holderX);
+        const traderYAfter = await getAccount(provider.connection, traderY);
+        expect(Number(holderXAfter.amount)).to.equal(0);
+        expect(Number(traderYAfter.amount)).to.be.greaterThan(Number(traderYBefore.amount));
+
+        // no output account is created for the holder when a recipient is given
+        const holderY = getAssociatedTokenAddressSync(mintY, holder.publicKey);
+        expect(await provider.connection.getAccountInfo(holderY)).to.be.null;
+    });
+
+    it("Swaps through swap_v2 without the mint accounts", async () => {
+        const traderX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintX,
+            trader.publicKey
+        );
+        const traderY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            trader.publicKey,
+        );
+
+        const traderXBefore = await getAccount(provider.connection, traderX.address);
+        const traderYBefore = await getAccount(provider.connection, traderY.address);
+
+        // the direction follows the input account's mint: Y -> X here
+        await program.methods
+            .swapV2(new anchor.BN(2_000_000), new anchor.BN(1))
+            .accounts({
+                user: trader.publicKey,
+                config: configPda,
+                userIn: traderY.address,
+                userOut: traderX.address,
+                vaultX: vaultXPda,
+                vaultY: vaultYPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+            })
+            .signers([trader])
+            .rpc();
+
+        const traderXAfter = await getAccount(provider.connection, traderX.address);
+        const traderYAfter = await getAccount(provider.connection, traderY.address);
+        expect(Number(traderXAfter.amount)).to.be.greaterThan(Number(traderXBefore.amount));
+        expect(Number(traderYBefore.amount) - Number(traderYAfter.amount)).to.equal(2_000_000);
+
+        // an output account of the input mint is rejected
+        try {
+            await program.methods
+                .swapV2(new anchor.BN(1_000_000), new anchor.BN(1))
+                .accounts({
+                    user: trader.publicKey,
+                    config: configPda,
+                    userIn: traderX.address,
+                    userOut: traderX.address,
+                    vaultX: vaultXPda,
+                    vaultY: vaultYPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([trader])
+                .rpc();
+
+            expect.fail("Should have failed with InvalidToken");
+        } catch (error: any) {
+            expect(error.message).to.include("InvalidToken");
+        }
+    });
+
+    it("Creates the output account for a first-time buyer through swap_v3", async () => {
+        const buyer = Keypair.generate();
+        await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(buyer.publicKey, 1_000_000_000)
+        );
+
+        const buyerX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            buyer,
+            mintX,
+            buyer.publicKey
+        );
+        await mintTo(provider.connection, authority, mintX, buyerX.address, authority, 1_000_000);
+        const buyerY = getAssociatedTokenAddressSync(mintY, buyer.publicKey);
+
+        await program.methods
+            .swapV3(new anchor.BN(1_000_000), new anchor.BN(1))
+            .accounts({
+                user: buyer.publicKey,
+                config: configPda,
+                mintIn: mintX,
+                mintOut: mintY,
+                userIn: buyerX.address,
+                userOut: buyerY,
+                vaultX: vaultXPda,
+                vaultY: vaultYPda,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([buyer])
+            .rpc();
+
+        const outAfter = await getAccount(provider.connection, buyerY);
+        expect(Number(outAfter.amount)).to.be.greaterThan(0);
+    });
+
     it("Fails when slippage exceeded", async () => {
         const traderX = await getOrCreateAssociatedTokenAccount(
             provider.connection,
@@ -320,4 +522,267 @@ describe("AMM Token Swapping", () => {
             expect(error.message).to.include("SlippageExceeded");
         }
     });
-});
\ No newline at end of file
+
+    it("Tracks volatility when dynamic fee is enabled", async () => {
+        await program.methods
+            .setDynamicFee(true, 30, 300, 10_000, new anchor.BN(3600))
+            .accounts({
+                authority: authority.publicKey,
+                config: configPda,
+                factory: factoryPda,
+            })
+            .signers([authority])
+            .rpc();
+
+        const traderX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintX,
+            trader.publicKey
+        );
+        const traderY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            trader.publicKey,
+        );
+
+        // two swaps: the first sets the reference price, the second moves it
+        for (const amountIn of [1_000_000, 5_000_000]) {
+            await program.methods
+                .swap(true, new anchor.BN(amountIn), new anchor.BN(1))
+                .accounts({
+                    user: trader.publicKey,
+                    config: configPda,
+                    mintX: mintX,
+                    mintY: mintY,
+                    userX: traderX.address,
+                    userY: traderY.address,
+                    vaultX: vaultXPda,
+                    vaultY: vaultYPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([trader])
+                .rpc();
+        }
+
+        const configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.dynamicFee.enabled).to.equal(1);
+        expect(configAccount.dynamicFee.referencePrice.toString()).to.not.equal("0");
+        expect(configAccount.dynamicFee.volatility).to.be.greaterThan(0);
+    });
+
+    it("Enforces the pool's trade size and price impact limits", async () => {
+        const traderX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintX,
+            trader.publicKey
+        );
+        const traderY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            trader.publicKey,
+        );
+
+        const swapX = (amountIn: number) =>
+            program.methods
+                .swap(true, new anchor.BN(amountIn), new anchor.BN(1))
+                .accounts({
+                    user: trader.publicKey,
+                    config: configPda,
+                    mintX: mintX,
+                    mintY: mintY,
+                    userX: traderX.address,
+                    userY: traderY.address,
+                    vaultX: vaultXPda,
+                    vaultY: vaultYPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([trader])
+                .rpc();
+
+        // trades up to 2% of the reserve, moving the price by at most 1%
+        await program.methods
+            .setSwapLimits(100, 200)
+            .accounts({
+                authority: authority.publicKey,
+                config: configPda,
+            })
+            .signers([authority])
+            .rpc();
+
+        // 3% of the X reserve
+        try {
+            await swapX(3_000_000);
+            expect.fail("Should have failed with TradeSizeExceeded");
+        } catch (error) {
+            expect(error.message).to.include("TradeSizeExceeded");
+        }
+
+        // 1.5% of the X reserve moves the price by ~3%
+        try {
+            await swapX(1_500_000);
+            expect.fail("Should have failed with PriceImpactExceeded");
+        } catch (error) {
+            expect(error.message).to.include("PriceImpactExceeded");
+        }
+
+        // small trades still go through
+        await swapX(100_000);
+    });
+
+    it("Locks the pool when the circuit breaker trips", async () => {
+        const traderX = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintX,
+            trader.publicKey
+        );
+        const traderY = await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            trader,
+            mintY,
+            trader.publicKey,
+        );
+
+        const swapX = (amountIn: number) =>
+            program.methods
+                .swap(true, new anchor.BN(amountIn), new anchor.BN(1))
+                .accounts({
+                    user: trader.publicKey,
+                    config: configPda,
+                    mintX: mintX,
+                    mintY: mintY,
+                    userX: traderX.address,
+                    userY: traderY.address,
+                    vaultX: vaultXPda,
+                    vaultY: vaultYPda,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([trader])
+                .rpc();
+        const setPause = (paused: number) =>
+            program.methods
+                .setPause(paused)
+                .accounts({
+                    authority: authority.publicKey,
+                    config: configPda,
+                })
+                .signers([authority])
+                .rpc();
+        const resetCircuitBreaker = () =>
+            program.methods
+                .resetCircuitBreaker()
+                .accounts({
+                    authority: authority.publicKey,
+                    config: configPda,
+                })
+                .signers([authority])
+                .rpc();
+
+        // lift the per-swap limits from the previous test
+        await program.methods
+            .setSwapLimits(0, 0)
+            .accounts({
+                authority: authority.publicKey,
+                config: configPda,
+            })
+            .signers([authority])
+            .rpc();
+
+        // trip after 1% of movement within an hour
+        await program.methods
+            .setCircuitBreaker(100, new anchor.BN(3600))
+            .accounts({
+                authority: authority.publicKey,
+                config: configPda,
+            })
+            .signers([authority])
+            .rpc();
+
+        // the first swap records the window's reference price and moves it ~0.2%,
+        // the second moves it ~4% away from that reference and trips
+        await swapX(100_000);
+        let configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.circuitBreaker.tripped).to.equal(0);
+
+        await swapX(2_000_000);
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.paused & 4).to.equal(4);
+        expect(configAccount.circuitBreaker.tripped).to.equal(1);
+        expect(configAccount.circuitBreaker.priceMove).to.be.greaterThan(100);
+
+        try {
+            await swapX(100_000);
+            expect.fail("Should have failed with SwapsPaused");
+        } catch (error) {
+            expect(error.message).to.include("SwapsPaused");
+        }
+
+        // the reset lifts the breaker's pause and starts a new window
+        await resetCircuitBreaker();
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.paused).to.equal(0);
+        expect(configAccount.circuitBreaker.tripped).to.equal(0);
+        await swapX(100_000);
+
+        // a manual swap pause survives a reset
+        await setPause(4);
+        await resetCircuitBreaker();
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.paused).to.equal(4);
+        await setPause(0);
+
+        // trip again, then resume through set_pause: the window restarts from the
+        // current price, so a small swap does not trip it again
+        await swapX(2_000_000);
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.circuitBreaker.tripped).to.equal(1);
+
+        await setPause(0);
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.circuitBreaker.tripped).to.equal(0);
+        expect(configAccount.circuitBreaker.referencePrice.toString()).to.equal("0");
+
+        await swapX(100_000);
+        configAccount = await program.account.config.fetch(configPda);
+        expect(configAccount.paused).to.equal(0);
+    });
+
+    it("Fails to set dynamic fee from non-authority", async () => {
+        try {
+            await program.methods
+                .setDynamicFee(true, 30, 300, 10_000, new anchor.BN(3600))
+                .accounts({
+                    authority: trader.publicKey,
+                    config: configPda,
+                    factory: factoryPda,
+                })
+                .signers([trader])
+                .rpc();
+            expect.fail("Should have failed with Unauthorized");
+        } catch (error) {
+            expect(error.message).to.include("Unauthorized");
+        }
+    });
+
+    it("Fails to set a dynamic fee outside the factory fee tiers", async () => {
+        try {
+            await program.methods
+                // 200 bps is not a whitelisted tier
+                .setDynamicFee(true, 30, 200, 10_000, new anchor.BN(3600))
+                .accounts({
+                    authority: authority.publicKey,
+                    config: configPda,
+                    factory: factoryPda,
+                })
+                .signers([authority])
+                .rpc();
+            expect.fail("Should have failed with FeeTierNotAllowed");
+        } catch (error) {
+            expect(error.message).to.include("FeeTierNotAllowed");
+        }
+    });
+});
@@ -0,0 +1,381 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+describe("AMM Single-Sided Withdraw", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let mintX: PublicKey;
+  let mintY: PublicKey;
+  let authority: Keypair;
+  let user: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let vaultXPda: PublicKey;
+  let vaultYPda: PublicKey;
+  let seed: anchor.BN;
+  const fee = 300; // 3%
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    user = Keypair.generate();
+
+    seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(user.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+
+    await mintTo(
+      provider.connection,
+      authority,
+      mintX,
+      userXAccount.address,
+      authority,
+      1_000_000_000
+    );
+    await mintTo(
+      provider.connection,
+      authority,
+      mintY,
+      userYAccount.address,
+      authority,
+      1_000_000_000
+    );
+  });
+
+  const depositLiquidity = async () => {
+    const userXAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintX,
+      user.publicKey
+    );
+    const userYAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      mintY,
+      user.publicKey
+    );
+    const userLpAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      user,
+      lpMintPda,
+      user.publicKey
+    );
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: userXAccount.address,
+        userY: userYAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    return { userXAccount, userYAccount, userLpAccount };
+  };
+
+  it("Successfully withdraws liquidity as token X only", async () => {
+    const { userXAccount, userYAccount, userLpAccount } = await depositLiquidity();
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    const lpToWithdraw = new anchor.BN(Number(lpBalance.amount) / 2);
+
+    const userXBefore = await getAccount(provider.connection, userXAccount.address);
+    const userYBefore = await getAccount(provider.connection, userYAccount.address);
+    const vaultYBefore = await getAccount(provider.connection, vaultYPda);
+
+    await program.methods
+      .withdrawSingle(lpToWithdraw, true, new anchor.BN(1))
+      .accounts({
+        user: user.publicKey,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        userOut: userXAccount.address,
+        userLp: userLpAccount.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+    const userXAfter = await getAccount(provider.connection, userXAccount.address);
+    const userYAfter = await getAccount(provider.connection, userYAccount.address);
+    const vaultYAfter = await getAccount(provider.connection, vaultYPda);
+
+    // half the LP is worth ~50 X directly, the Y side is swapped into more X
+    const received = Number(userXAfter.amount) - Number(userXBefore.amount);
+    expect(received).to.be.greaterThan(50_000_000);
+    expect(received).to.be.lessThan(100_000_000);
+
+    // user receives no Y and the Y side stays in the pool
+    expect(userYAfter.amount.toString()).to.equal(userYBefore.amount.toString());
+    expect(vaultYAfter.amount.toString()).to.equal(vaultYBefore.amount.toString());
+  });
+
+  it("Fails when output account does not match the requested side", async () => {
+    const { userYAccount, userLpAccount } = await depositLiquidity();
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    const lpToWithdraw = new anchor.BN(Number(lpBalance.amount) / 2);
+
+    try {
+      await program.methods
+        .withdrawSingle(lpToWithdraw, true, new anchor.BN(1))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userOut: userYAccount.address, // Y account for an X withdrawal
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with invalid token");
+    } catch (error: any) {
+      expect(error.message).to.include("InvalidToken");
+    }
+  });
+
+  it("Fails single-sided withdraw with slippage exceeded", async () => {
+    const { userYAccount, userLpAccount } = await depositLiquidity();
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    const lpToWithdraw = new anchor.BN(Number(lpBalance.amount) / 2);
+
+    try {
+      await program.methods
+        // proportional share of Y alone is ~100 Y, swapping X in cannot double it
+        .withdrawSingle(lpToWithdraw, false, new anchor.BN(200_000_000))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userOut: userYAccount.address,
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with slippage exceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+  });
+
+  it("Fails when the swap leg exceeds the pool's max trade size", async () => {
+    const { userXAccount, userLpAccount } = await depositLiquidity();
+
+    // trades up to 1% of the reserve, no price impact limit
+    await program.methods
+      .setSwapLimits(0, 100)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    const lpToWithdraw = new anchor.BN(Number(lpBalance.amount) / 2);
+
+    try {
+      await program.methods
+        // half the Y side is swapped back against the remaining half
+        .withdrawSingle(lpToWithdraw, true, new anchor.BN(1))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userOut: userXAccount.address,
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with TradeSizeExceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("TradeSizeExceeded");
+    }
+  });
+
+  it("Fails while swaps are paused", async () => {
+    const { userXAccount, userLpAccount } = await depositLiquidity();
+
+    // the swap leg moves the price, so a swap pause blocks it
+    await program.methods
+      .setPause(4)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+    const lpToWithdraw = new anchor.BN(Number(lpBalance.amount) / 2);
+
+    try {
+      await program.methods
+        .withdrawSingle(lpToWithdraw, true, new anchor.BN(1))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userOut: userXAccount.address,
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with SwapsPaused");
+    } catch (error: any) {
+      expect(error.message).to.include("SwapsPaused");
+    }
+  });
+
+  it("Fails when withdrawing the entire LP supply", async () => {
+    const { userXAccount, userLpAccount } = await depositLiquidity();
+
+    // the depositor holds the whole supply, nothing is left to swap against
+    const lpBalance = await getAccount(provider.connection, userLpAccount.address);
+
+    try {
+      await program.methods
+        .withdrawSingle(new anchor.BN(Number(lpBalance.amount)), true, new anchor.BN(1))
+        .accounts({
+          user: user.publicKey,
+          config: configPda,
+          mintX: mintX,
+          mintY: mintY,
+          lpMint: lpMintPda,
+          userOut: userXAccount.address,
+          userLp: userLpAccount.address,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+      expect.fail("Should have failed with EntireSupplyWithdraw");
+    } catch (error: any) {
+      expect(error.message).to.include("EntireSupplyWithdraw");
+    }
+  });
+});
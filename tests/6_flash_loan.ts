@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  createTransferInstruction,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+// SPL Memo program, deployed on the local validator by default
+const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+describe("AMM Flash Loan", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let mintX: PublicKey;
+  let mintY: PublicKey;
+  let authority: Keypair;
+  let borrower: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let vaultXPda: PublicKey;
+  let vaultYPda: PublicKey;
+  let borrowerX: PublicKey;
+  let borrowerY: PublicKey;
+  let seed: anchor.BN;
+  const fee = 30; // 0.3%
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    borrower = Keypair.generate();
+
+    seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(borrower.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // seed liquidity by authority
+    const authX = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintX,
+      authority.publicKey
+    );
+    const authY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority, mintX, authX.address, authority, 1_000_000_000);
+    await mintTo(provider.connection, authority, mintY, authY.address, authority, 1_000_000_000);
+
+    const authLp = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      lpMintPda,
+      authority.publicKey
+    );
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: authX.address,
+        userY: authY.address,
+        userLp: authLp.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // borrower holds a little X to cover the flash fee
+    borrowerX = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, borrower, mintX, borrower.publicKey)
+    ).address;
+    borrowerY = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, borrower, mintY, borrower.publicKey)
+    ).address;
+    await mintTo(provider.connection, authority, mintX, borrowerX, authority, 1_000_000);
+  });
+
+  it("Lends and receives repayment with fee", async () => {
+    const amountX = 10_000_000;
+    const flashFee = Math.ceil((amountX * 9) / 10_000);
+
+    // the token program acts as receiver: it moves loan plus fee back to the vault
+    const repay = createTransferInstruction(
+      borrowerX,
+      vaultXPda,
+      borrower.publicKey,
+      amountX + flashFee
+    );
+
+    const vaultXBefore = await getAccount(provider.connection, vaultXPda);
+
+    await program.methods
+      .flashLoan(new anchor.BN(amountX), new anchor.BN(0), repay.data)
+      .accounts({
+        user: borrower.publicKey,
+        config: configPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        receiverX: borrowerX,
+        receiverY: borrowerY,
+        receiverProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(repay.keys)
+      .signers([borrower])
+      .rpc();
+
+    const vaultXAfter = await getAccount(provider.connection, vaultXPda);
+    expect(Number(vaultXAfter.amount) - Number(vaultXBefore.amount)).to.equal(flashFee);
+
+    const configAccount = await program.account.config.fetch(configPda);
+    expect(configAccount.flashLoanActive).to.equal(0);
+  });
+
+  it("Fails when the loan is not repaid", async () => {
+    try {
+      await program.methods
+        .flashLoan(new anchor.BN(10_000_000), new anchor.BN(0), Buffer.from("no repayment"))
+        .accounts({
+          user: borrower.publicKey,
+          config: configPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          receiverX: borrowerX,
+          receiverY: borrowerY,
+          receiverProgram: MEMO_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([borrower])
+        .rpc();
+
+      expect.fail("Should have failed with FlashLoanNotRepaid");
+    } catch (error: any) {
+      expect(error.message).to.include("FlashLoanNotRepaid");
+    }
+  });
+
+  it("Fails when the receiver is the AMM program itself", async () => {
+    try {
+      await program.methods
+        .flashLoan(new anchor.BN(10_000_000), new anchor.BN(0), Buffer.from([]))
+        .accounts({
+          user: borrower.publicKey,
+          config: configPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          receiverX: borrowerX,
+          receiverY: borrowerY,
+          receiverProgram: program.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([borrower])
+        .rpc();
+
+      expect.fail("Should have failed with InvalidFlashLoanReceiver");
+    } catch (error: any) {
+      expect(error.message).to.include("InvalidFlashLoanReceiver");
+    }
+  });
+});
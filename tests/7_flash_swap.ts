@@ -0,0 +1,245 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+  createTransferInstruction,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+describe("AMM Flash Swap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let mintX: PublicKey;
+  let mintY: PublicKey;
+  let authority: Keypair;
+  let borrower: Keypair;
+  let configPda: PublicKey;
+  let lpMintPda: PublicKey;
+  let vaultXPda: PublicKey;
+  let vaultYPda: PublicKey;
+  let borrowerX: PublicKey;
+  let borrowerY: PublicKey;
+  let seed: anchor.BN;
+  const fee = 30; // 0.3%
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    borrower = Keypair.generate();
+
+    seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(borrower.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // seed liquidity by authority
+    const authX = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintX,
+      authority.publicKey
+    );
+    const authY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority, mintX, authX.address, authority, 1_000_000_000);
+    await mintTo(provider.connection, authority, mintY, authY.address, authority, 1_000_000_000);
+
+    const authLp = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      lpMintPda,
+      authority.publicKey
+    );
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(200_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: authX.address,
+        userY: authY.address,
+        userLp: authLp.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    // borrower holds X to pay for the Y taken out
+    borrowerX = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, borrower, mintX, borrower.publicKey)
+    ).address;
+    borrowerY = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, borrower, mintY, borrower.publicKey)
+    ).address;
+    await mintTo(provider.connection, authority, mintX, borrowerX, authority, 1_000_000);
+  });
+
+  it("Sends Y first and accepts payment in X", async () => {
+    const amountYOut = 1_000_000;
+
+    // the token program acts as callback: it pays X into the vault
+    const pay = createTransferInstruction(borrowerX, vaultXPda, borrower.publicKey, 600_000);
+
+    const borrowerYBefore = await getAccount(provider.connection, borrowerY);
+
+    await program.methods
+      .flashSwap(new anchor.BN(0), new anchor.BN(amountYOut), pay.data)
+      .accounts({
+        user: borrower.publicKey,
+        config: configPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        receiverX: borrowerX,
+        receiverY: borrowerY,
+        callbackProgram: TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(pay.keys)
+      .signers([borrower])
+      .rpc();
+
+    const borrowerYAfter = await getAccount(provider.connection, borrowerY);
+    expect(Number(borrowerYAfter.amount) - Number(borrowerYBefore.amount)).to.equal(amountYOut);
+
+    const configAccount = await program.account.config.fetch(configPda);
+    expect(configAccount.flashLoanActive).to.equal(0);
+  });
+
+  it("Fails when the payment breaks the invariant", async () => {
+    // ~504_000 X is needed for 1 Y at a 1:2 price with 0.3% fee
+    const pay = createTransferInstruction(borrowerX, vaultXPda, borrower.publicKey, 100_000);
+
+    try {
+      await program.methods
+        .flashSwap(new anchor.BN(0), new anchor.BN(1_000_000), pay.data)
+        .accounts({
+          user: borrower.publicKey,
+          config: configPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          receiverX: borrowerX,
+          receiverY: borrowerY,
+          callbackProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(pay.keys)
+        .signers([borrower])
+        .rpc();
+
+      expect.fail("Should have failed with SlippageExceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+  });
+
+  it("Fails when the net trade exceeds the pool's max trade size", async () => {
+    // trades up to 0.5% of the reserve, no price impact limit
+    await program.methods
+      .setSwapLimits(0, 50)
+      .accounts({
+        authority: authority.publicKey,
+        config: configPda,
+      })
+      .signers([authority])
+      .rpc();
+
+    // 600_000 X paid in is 0.6% of the X reserve
+    const pay = createTransferInstruction(borrowerX, vaultXPda, borrower.publicKey, 600_000);
+
+    try {
+      await program.methods
+        .flashSwap(new anchor.BN(0), new anchor.BN(1_000_000), pay.data)
+        .accounts({
+          user: borrower.publicKey,
+          config: configPda,
+          vaultX: vaultXPda,
+          vaultY: vaultYPda,
+          receiverX: borrowerX,
+          receiverY: borrowerY,
+          callbackProgram: TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(pay.keys)
+        .signers([borrower])
+        .rpc();
+
+      expect.fail("Should have failed with TradeSizeExceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("TradeSizeExceeded");
+    }
+  });
+});
@@ -0,0 +1,237 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+type Pool = {
+  config: PublicKey;
+  vaultX: PublicKey;
+  vaultY: PublicKey;
+};
+
+describe("AMM Route Swap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+  let mintC: PublicKey;
+  let authority: Keypair;
+  let trader: Keypair;
+  let poolAB: Pool;
+  let poolBC: Pool;
+  let traderA: PublicKey;
+  let traderC: PublicKey;
+  const fee = 30; // 0.3%
+
+  // creates a pool for (mintX, mintY) and seeds it with liquidity from authority
+  const createPool = async (mintX: PublicKey, mintY: PublicKey): Promise<Pool> => {
+    const seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const authX = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintX,
+      authority.publicKey
+    );
+    const authY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+    const authLp = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      lpMintPda,
+      authority.publicKey
+    );
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(100_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: authX.address,
+        userY: authY.address,
+        userLp: authLp.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    return { config: configPda, vaultX: vaultXPda, vaultY: vaultYPda };
+  };
+
+  const poolAccounts = (pool: Pool) => [
+    { pubkey: pool.config, isSigner: false, isWritable: true },
+    { pubkey: pool.vaultX, isSigner: false, isWritable: true },
+    { pubkey: pool.vaultY, isSigner: false, isWritable: true },
+  ];
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    trader = Keypair.generate();
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(trader.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    mintA = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    mintB = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    mintC = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    for (const mint of [mintA, mintB, mintC]) {
+      const ata = await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        authority,
+        mint,
+        authority.publicKey
+      );
+      await mintTo(provider.connection, authority, mint, ata.address, authority, 1_000_000_000);
+    }
+
+    poolAB = await createPool(mintA, mintB);
+    poolBC = await createPool(mintB, mintC);
+
+    traderA = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, trader, mintA, trader.publicKey)
+    ).address;
+    traderC = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, trader, mintC, trader.publicKey)
+    ).address;
+    await mintTo(provider.connection, authority, mintA, traderA, authority, 10_000_000);
+  });
+
+  it("Swaps A to C through two pools", async () => {
+    const amountIn = 1_000_000;
+
+    await program.methods
+      .routeSwap(new anchor.BN(amountIn), new anchor.BN(1), 2)
+      .accounts({
+        user: trader.publicKey,
+        userIn: traderA,
+        userOut: traderC,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([...poolAccounts(poolAB), ...poolAccounts(poolBC)])
+      .signers([trader])
+      .rpc();
+
+    const traderCAccount = await getAccount(provider.connection, traderC);
+    expect(Number(traderCAccount.amount)).to.be.greaterThan(0);
+
+    // intermediate token stays in the pools
+    const vaultBInAB = await getAccount(provider.connection, poolAB.vaultY);
+    const vaultBInBC = await getAccount(provider.connection, poolBC.vaultX);
+    expect(Number(vaultBInAB.amount) + Number(vaultBInBC.amount)).to.equal(200_000_000);
+  });
+
+  it("Fails when the final output is below min_out", async () => {
+    try {
+      await program.methods
+        .routeSwap(new anchor.BN(1_000_000), new anchor.BN(1_000_000), 2)
+        .accounts({
+          user: trader.publicKey,
+          userIn: traderA,
+          userOut: traderC,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([...poolAccounts(poolAB), ...poolAccounts(poolBC)])
+        .signers([trader])
+        .rpc();
+
+      expect.fail("Should have failed with SlippageExceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+  });
+
+  it("Fails when the pools do not chain", async () => {
+    try {
+      await program.methods
+        .routeSwap(new anchor.BN(1_000_000), new anchor.BN(1), 2)
+        .accounts({
+          user: trader.publicKey,
+          userIn: traderA,
+          userOut: traderC,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([...poolAccounts(poolBC), ...poolAccounts(poolAB)])
+        .signers([trader])
+        .rpc();
+
+      expect.fail("Should have failed with InvalidToken");
+    } catch (error: any) {
+      expect(error.message).to.include("InvalidToken");
+    }
+  });
+});
@@ -0,0 +1,234 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  mintTo,
+  getOrCreateAssociatedTokenAccount,
+  getAccount,
+} from "@solana/spl-token";
+import { Amm } from "../target/types/amm";
+import { expect } from "chai";
+
+type Pool = {
+  config: PublicKey;
+  vaultX: PublicKey;
+  vaultY: PublicKey;
+  traderX: PublicKey;
+  traderY: PublicKey;
+};
+
+describe("AMM Batch Swap", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Amm as Program<Amm>;
+
+  const [factoryPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("factory")],
+    program.programId,
+  );
+
+  let authority: Keypair;
+  let trader: Keypair;
+  let poolA: Pool;
+  let poolB: Pool;
+  const fee = 30; // 0.3%
+
+  // creates a fresh pool seeded by authority and funds the trader with X
+  const createPool = async (): Promise<Pool> => {
+    const seed = new anchor.BN(Math.floor(Math.random() * 1_000_000));
+    const mintX = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+    const mintY = await createMint(provider.connection, authority, authority.publicKey, null, 6);
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [lpMintPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultXPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_x"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const [vaultYPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault_y"), seed.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // next registry slot in the factory
+    const factoryAccount = await program.account.factory.fetch(factoryPda);
+    const [poolIndexPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_index"), factoryAccount.poolCount.toArrayLike(Buffer, "le", 8)],
+      program.programId,
+    );
+
+    await program.methods
+      .initialize(seed, fee)
+      .accounts({
+        authority: authority.publicKey,
+        factory: factoryPda,
+        config: configPda,
+        mintX: mintX,
+        mintY: mintY,
+        lpMint: lpMintPda,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        poolIndex: poolIndexPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const authX = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintX,
+      authority.publicKey
+    );
+    const authY = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      mintY,
+      authority.publicKey
+    );
+    const authLp = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      authority,
+      lpMintPda,
+      authority.publicKey
+    );
+    await mintTo(provider.connection, authority, mintX, authX.address, authority, 1_000_000_000);
+    await mintTo(provider.connection, authority, mintY, authY.address, authority, 1_000_000_000);
+
+    await program.methods
+      .deposit(new anchor.BN(100_000_000), new anchor.BN(100_000_000), new anchor.BN(1), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        user: authority.publicKey,
+        config: configPda,
+        lpMint: lpMintPda,
+        userX: authX.address,
+        userY: authY.address,
+        userLp: authLp.address,
+        vaultX: vaultXPda,
+        vaultY: vaultYPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+
+    const traderX = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, trader, mintX, trader.publicKey)
+    ).address;
+    const traderY = (
+      await getOrCreateAssociatedTokenAccount(provider.connection, trader, mintY, trader.publicKey)
+    ).address;
+    await mintTo(provider.connection, authority, mintX, traderX, authority, 10_000_000);
+
+    return { config: configPda, vaultX: vaultXPda, vaultY: vaultYPda, traderX, traderY };
+  };
+
+  // accounts of an X to Y leg
+  const legAccounts = (pool: Pool) => [
+    { pubkey: pool.config, isSigner: false, isWritable: true },
+    { pubkey: pool.vaultX, isSigner: false, isWritable: true },
+    { pubkey: pool.vaultY, isSigner: false, isWritable: true },
+    { pubkey: pool.traderX, isSigner: false, isWritable: true },
+    { pubkey: pool.traderY, isSigner: false, isWritable: true },
+  ];
+
+  beforeEach(async () => {
+    authority = Keypair.generate();
+    trader = Keypair.generate();
+
+    await Promise.all([
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(authority.publicKey, 2_000_000_000)
+      ),
+      provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(trader.publicKey, 2_000_000_000)
+      ),
+    ]);
+
+    poolA = await createPool();
+    poolB = await createPool();
+  });
+
+  it("Executes swaps on independent pools", async () => {
+    const legs = [
+      { isXToY: true, amountIn: new anchor.BN(1_000_000), minOut: new anchor.BN(1) },
+      { isXToY: true, amountIn: new anchor.BN(2_000_000), minOut: new anchor.BN(1) },
+    ];
+
+    await program.methods
+      .batchSwap(legs, true)
+      .accounts({
+        user: trader.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([...legAccounts(poolA), ...legAccounts(poolB)])
+      .signers([trader])
+      .rpc();
+
+    const traderAY = await getAccount(provider.connection, poolA.traderY);
+    const traderBY = await getAccount(provider.connection, poolB.traderY);
+    expect(Number(traderAY.amount)).to.be.greaterThan(0);
+    expect(Number(traderBY.amount)).to.be.greaterThan(Number(traderAY.amount));
+  });
+
+  it("Skips a failing leg when not all-or-nothing", async () => {
+    const legs = [
+      { isXToY: true, amountIn: new anchor.BN(1_000_000), minOut: new anchor.BN(1) },
+      // unreachable min_out
+      { isXToY: true, amountIn: new anchor.BN(1_000_000), minOut: new anchor.BN(1_000_000) },
+    ];
+
+    await program.methods
+      .batchSwap(legs, false)
+      .accounts({
+        user: trader.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([...legAccounts(poolA), ...legAccounts(poolB)])
+      .signers([trader])
+      .rpc();
+
+    const traderAY = await getAccount(provider.connection, poolA.traderY);
+    const traderBY = await getAccount(provider.connection, poolB.traderY);
+    expect(Number(traderAY.amount)).to.be.greaterThan(0);
+    expect(Number(traderBY.amount)).to.equal(0);
+  });
+
+  it("Fails the whole batch when all-or-nothing", async () => {
+    const legs = [
+      { isXToY: true, amountIn: new anchor.BN(1_000_000), minOut: new anchor.BN(1) },
+      { isXToY: true, amountIn: new anchor.BN(1_000_000), minOut: new anchor.BN(1_000_000) },
+    ];
+
+    try {
+      await program.methods
+        .batchSwap(legs, true)
+        .accounts({
+          user: trader.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts([...legAccounts(poolA), ...legAccounts(poolB)])
+        .signers([trader])
+        .rpc();
+
+      expect.fail("Should have failed with SlippageExceeded");
+    } catch (error: any) {
+      expect(error.message).to.include("SlippageExceeded");
+    }
+
+    const traderAY = await getAccount(provider.connection, poolA.traderY);
+    expect(Number(traderAY.amount)).to.equal(0);
+  });
+});